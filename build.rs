@@ -0,0 +1,21 @@
+// Con la característica `ffi` se genera la cabecera C de la interfaz en
+// OUT_DIR. La copia de include/rusttery.h se comprueba contra ella en las
+// pruebas de src/ffi.
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi/mod.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(
+                cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap(),
+            )
+            .generate()
+            .expect("No se pudo generar la cabecera C")
+            .write_to_file(format!("{}/rusttery.h", out_dir));
+    }
+}
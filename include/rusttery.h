@@ -0,0 +1,139 @@
+/* Generado por cbindgen a partir de src/ffi/mod.rs; no editar.
+ *
+ * Los objetos se liberan con su función *_free y las cadenas devueltas con
+ * rusttery_string_free. Los valores desconocidos se devuelven como -1 o NAN.
+ * Si una función devuelve un RustteryStatus distinto de RUSTTERY_STATUS_OK,
+ * rusttery_last_error() describe el motivo. */
+
+#ifndef RUSTTERY_H
+#define RUSTTERY_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum {
+  RUSTTERY_STATUS_OK = 0,
+  RUSTTERY_STATUS_NO_BATTERY = 1,
+  RUSTTERY_STATUS_UNKNOWN_BATTERY = 2,
+  RUSTTERY_STATUS_READ_FAILED = 3,
+  RUSTTERY_STATUS_PERMISSION_DENIED = 4,
+  RUSTTERY_STATUS_UNSUPPORTED = 5,
+  RUSTTERY_STATUS_DATABASE_ERROR = 6,
+  RUSTTERY_STATUS_INVALID_ARGUMENT = 7,
+  RUSTTERY_STATUS_INTERNAL_ERROR = 8,
+} RustteryStatus;
+
+typedef struct RustteryBattery RustteryBattery;
+
+typedef struct RustteryBatteryList RustteryBatteryList;
+
+typedef struct RustteryDatabase RustteryDatabase;
+
+typedef struct RustteryHistory RustteryHistory;
+
+typedef struct {
+  double avg_percent;
+  int32_t min_percent;
+  int32_t max_percent;
+  double avg_power;
+  int64_t total_records;
+} RustteryStatistics;
+
+typedef struct {
+  int64_t timestamp;
+  int32_t percent;
+  int32_t health;
+  double percent_exact;
+  double power_now;
+  int32_t time_to_empty;
+  int32_t time_to_full;
+} RustteryHistoryRecord;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Mensaje del último error en este hilo, o NULL si la última llamada tuvo
+// éxito. Sale en el idioma de la locale (español o inglés) y, si no es
+// ninguno de los dos, en inglés.
+char *rusttery_last_error(void);
+
+void rusttery_string_free(char *value);
+
+// Lee la batería `name`, o la vista combinada si `name` es NULL.
+RustteryStatus rusttery_battery_read(const char *name, RustteryBattery **out);
+
+void rusttery_battery_free(RustteryBattery *battery);
+
+RustteryStatus rusttery_battery_list(RustteryBatteryList **out);
+
+size_t rusttery_battery_list_len(const RustteryBatteryList *list);
+
+// La batería pertenece a la lista y es válida hasta liberarla; no debe
+// pasarse a `rusttery_battery_free`.
+const RustteryBattery *rusttery_battery_list_get(const RustteryBatteryList *list, size_t index);
+
+void rusttery_battery_list_free(RustteryBatteryList *list);
+
+char *rusttery_battery_name(const RustteryBattery *battery);
+
+char *rusttery_battery_status(const RustteryBattery *battery);
+
+int32_t rusttery_battery_percent(const RustteryBattery *battery);
+
+double rusttery_battery_percent_exact(const RustteryBattery *battery);
+
+int32_t rusttery_battery_health(const RustteryBattery *battery);
+
+double rusttery_battery_power_now(const RustteryBattery *battery);
+
+int32_t rusttery_battery_time_to_empty(const RustteryBattery *battery);
+
+int32_t rusttery_battery_time_to_full(const RustteryBattery *battery);
+
+// Todos los campos de la batería como objeto JSON.
+char *rusttery_battery_json(const RustteryBattery *battery);
+
+// Abre la base de datos en `path`, o en la ubicación por defecto si es
+// NULL. Con `retention_days` mayor que 0 se borran los registros más
+// antiguos de ese número de días; con 0 se conserva todo el historial.
+RustteryStatus rusttery_database_open(const char *path,
+                                      uint32_t retention_days,
+                                      RustteryDatabase **out);
+
+void rusttery_database_free(RustteryDatabase *db);
+
+// Historial de las últimas `hours` horas, de una batería o de todas si
+// `battery` es NULL.
+RustteryStatus rusttery_database_history(const RustteryDatabase *db,
+                                         int64_t hours,
+                                         const char *battery,
+                                         RustteryHistory **out);
+
+RustteryStatus rusttery_database_statistics(const RustteryDatabase *db,
+                                            int64_t hours,
+                                            const char *battery,
+                                            RustteryStatistics *out);
+
+size_t rusttery_history_len(const RustteryHistory *history);
+
+RustteryStatus rusttery_history_get(const RustteryHistory *history,
+                                    size_t index,
+                                    RustteryHistoryRecord *out);
+
+// Nombre de la batería del registro, o NULL si no consta.
+char *rusttery_history_battery(const RustteryHistory *history, size_t index);
+
+// El historial completo como array JSON.
+char *rusttery_history_json(const RustteryHistory *history);
+
+void rusttery_history_free(RustteryHistory *history);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTTERY_H */
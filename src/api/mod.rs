@@ -1,31 +1,104 @@
+use std::path::PathBuf;
+use std::sync::Arc;
 use tiny_http::{Server, Response, Header};
-use crate::core::get_battery_info;
-use crate::database::Database;
-
-pub fn start_server() {
-    let server = Server::http("0.0.0.0:3000").expect("No se pudo iniciar el servidor en puerto 3000");
-    let db = Database::new().expect("No se pudo iniciar la base de datos");
-    
-    println!("Servidor Rusttery ejecutándose en http://localhost:3000");
-    println!("\n Endpoints disponibles:");
-    println!("  GET /api/v1/battery/check - Carga actual y salud");
-    println!("  GET /api/v1/battery/health - Solo salud");
-    println!("  GET /api/v1/battery/status - Solo carga actual");
-    println!("  GET /api/v1/battery/full - Información completa");
-    println!("  GET /api/v1/battery/history?hours=24 - Historial de carga");
-    println!("  GET /api/v1/battery/statistics?hours=24 - Estadísticas\n");
-    for request in server.incoming_requests() {
+use crate::core::{
+    on_external_power, BatteryError, BatteryInfo, BatteryMonitor, ChargeThresholds,
+    DeviceBatteryInfo, PowerSourceInfo, Snapshot, ThresholdUpdate,
+};
+use crate::database::{Database, DEFAULT_RETENTION_DAYS};
+use crate::i18n::messages;
+
+// Rutas que se anuncian al arrancar, en el orden de sus descripciones en el
+// catálogo de mensajes.
+const ENDPOINTS: [&str; 12] = [
+    "GET /api/v1/battery/check",
+    "GET /api/v1/battery/health",
+    "GET /api/v1/battery/status",
+    "GET /api/v1/battery/full",
+    "GET /api/v1/batteries",
+    "GET /api/v1/power",
+    "GET /api/v1/devices",
+    "GET /api/v1/battery/history?hours=24",
+    "GET /api/v1/battery/statistics?hours=24",
+    "GET /api/v1/events?hours=24",
+    "GET /api/v1/battery/thresholds",
+    "POST /api/v1/battery/thresholds",
+];
+
+pub struct ServerOptions {
+    pub bind: String,
+    pub port: u16,
+    // Sin ruta se usa la base de datos del directorio de datos del usuario.
+    pub db_path: Option<PathBuf>,
+    pub retention_days: u32,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            bind: "0.0.0.0".to_string(),
+            port: 3000,
+            db_path: None,
+            retention_days: DEFAULT_RETENTION_DAYS,
+        }
+    }
+}
+
+impl ServerOptions {
+    fn open_database(&self) -> rusqlite::Result<Database> {
+        let path = self.db_path.clone().unwrap_or_else(Database::default_path);
+        Database::open_with_retention(path, self.retention_days)
+    }
+}
+
+pub fn start_server(monitor: &BatteryMonitor, options: &ServerOptions) {
+    let t = messages();
+    let source = monitor.source();
+    let server = Server::http((options.bind.as_str(), options.port)).unwrap_or_else(|_| {
+        panic!("{}", (t.server_failed)(&options.bind, options.port))
+    });
+    let db = options.open_database().expect(t.database_failed);
+    let api_token = std::env::var("RUSTTERY_API_TOKEN").ok().filter(|t| !t.is_empty());
+
+    // Los eventos de conexión se guardan en cuanto llegan, con su propia
+    // conexión a la base de datos.
+    let snapshots = monitor.subscribe();
+    let events_db = options.open_database();
+    std::thread::spawn(move || {
+        let Ok(events_db) = events_db else {
+            return;
+        };
+        for snapshot in snapshots {
+            for event in &snapshot.events {
+                let _ = events_db.save_event(event);
+            }
+        }
+    });
+
+    let host = match options.bind.as_str() {
+        "0.0.0.0" | "::" => "localhost",
+        bind => bind,
+    };
+    println!("{}", (t.server_running)(host, options.port));
+    println!("\n {}", t.endpoints);
+    for (endpoint, description) in ENDPOINTS.iter().zip(t.endpoint_descriptions) {
+        println!("  {} - {}", endpoint, description);
+    }
+    println!("  {}\n", t.battery_param_hint);
+    for mut request in server.incoming_requests() {
         let url = request.url().to_string();
         
         let cors_headers = vec![
             Header::from_bytes(&b"Access-Control-Allow-Origin"[..], &b"*"[..]).unwrap(),
-            Header::from_bytes(&b"Access-Control-Allow-Methods"[..], &b"GET, OPTIONS"[..]).unwrap(),
+            Header::from_bytes(&b"Access-Control-Allow-Methods"[..], &b"GET, POST, OPTIONS"[..]).unwrap(),
             Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+            Header::from_bytes(&b"Access-Control-Allow-Headers"[..], &b"Authorization, Content-Type"[..]).unwrap(),
         ];
 
         if request.method().as_str() == "OPTIONS" {
             let response = Response::empty(204).with_header(cors_headers[0].clone())
-                .with_header(cors_headers[1].clone());
+                .with_header(cors_headers[1].clone())
+                .with_header(cors_headers[3].clone());
             let _ = request.respond(response);
             continue;
         }
@@ -34,81 +107,222 @@ pub fn start_server() {
 
         match path {
             "/api/v1/battery/check" => {
-                if let Some(info) = get_battery_info() {
-                    let json = serde_json::json!({
-                        "status": "ok",
-                        "data": {
-                            "percent": info.percent,
-                            "health": info.health
-                        }
-                    });
-                    
-                    let response = Response::from_string(json.to_string())
-                        .with_header(cors_headers[0].clone())
-                        .with_header(cors_headers[2].clone());
-                    let _ = request.respond(response);
-                } else {
-                    send_error(request, &cors_headers, 500, "No se pudo obtener información de la batería");
+                match select_battery(&monitor.latest(), query) {
+                    Ok(info) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "battery": info.name,
+                                "percent": info.percent,
+                                "health": info.health
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
                 }
             }
             "/api/v1/battery/health" => {
-                if let Some(info) = get_battery_info() {
-                    let json = serde_json::json!({
-                        "status": "ok",
-                        "data": {
-                            "health": info.health
-                        }
-                    });
-                    
-                    let response = Response::from_string(json.to_string())
-                        .with_header(cors_headers[0].clone())
-                        .with_header(cors_headers[2].clone());
-                    let _ = request.respond(response);
-                } else {
-                    send_error(request, &cors_headers, 500, "No se pudo obtener información de la batería");
+                match select_battery(&monitor.latest(), query) {
+                    Ok(info) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "battery": info.name,
+                                "health": info.health
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
                 }
             }
             "/api/v1/battery/status" => {
-                if let Some(info) = get_battery_info() {
-                    let json = serde_json::json!({
-                        "status": "ok",
-                        "data": {
-                            "percent": info.percent
-                        }
-                    });
-                    
-                    let response = Response::from_string(json.to_string())
-                        .with_header(cors_headers[0].clone())
-                        .with_header(cors_headers[2].clone());
-                    let _ = request.respond(response);
-                } else {
-                    send_error(request, &cors_headers, 500, "No se pudo obtener información de la batería");
+                match select_battery(&monitor.latest(), query) {
+                    Ok(info) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "battery": info.name,
+                                "percent": info.percent
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
                 }
             }
             "/api/v1/battery/full" => {
-                if let Some(info) = get_battery_info() {
-                    let json = serde_json::json!({
-                        "status": "ok",
-                        "data": info
-                    });
-                    
-                    let response = Response::from_string(json.to_string())
-                        .with_header(cors_headers[0].clone())
-                        .with_header(cors_headers[2].clone());
-                    let _ = request.respond(response);
-                } else {
-                    send_error(request, &cors_headers, 500, "No se pudo obtener información de la batería");
+                match select_battery(&monitor.latest(), query) {
+                    Ok(info) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": info
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
+                }
+            }
+            "/api/v1/batteries" => {
+                let snapshot = monitor.latest();
+                match snapshot.battery(None) {
+                    Ok(combined) => {
+                        let batteries = &snapshot.batteries;
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "combined": combined,
+                                "batteries": batteries
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
+                }
+            }
+            "/api/v1/power" => {
+                let snapshot = monitor.latest();
+                match power_sources(&snapshot) {
+                    Ok(sources) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "external_power": on_external_power(sources),
+                                "sources": sources
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
+                }
+            }
+            "/api/v1/devices" => {
+                let snapshot = monitor.latest();
+                match devices(&snapshot) {
+                    Ok(devices) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "devices": devices
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
+                }
+            }
+            "/api/v1/battery/thresholds" => {
+                let targets = match parse_param(query, "battery") {
+                    Some(name) => Ok(vec![name.to_string()]),
+                    None => source
+                        .batteries()
+                        .map(|batteries| batteries.into_iter().map(|b| b.name).collect::<Vec<_>>()),
+                };
+
+                let targets = match targets {
+                    Ok(targets) if targets.is_empty() => Err(BatteryError::NoBattery),
+                    other => other,
+                };
+
+                let result: Result<Vec<ChargeThresholds>, BatteryError> = match request.method().as_str() {
+                    "GET" => targets.and_then(|targets| {
+                        targets.iter().map(|name| source.charge_thresholds(name)).collect()
+                    }),
+                    "POST" => {
+                        if let Err((code, message)) = check_token(&request, api_token.as_deref()) {
+                            send_error(request, &cors_headers, code, message);
+                            continue;
+                        }
+
+                        let mut body = String::new();
+                        if request.as_reader().read_to_string(&mut body).is_err() {
+                            send_error(request, &cors_headers, 400, t.body_unreadable);
+                            continue;
+                        }
+
+                        let update: ThresholdUpdate = match serde_json::from_str(&body) {
+                            Ok(update) => update,
+                            Err(_) => {
+                                send_error(request, &cors_headers, 400, t.invalid_json);
+                                continue;
+                            }
+                        };
+
+                        // Se comprueba el cambio en todas las baterías antes de
+                        // escribir en ninguna, para no dejarlas a medias.
+                        targets.and_then(|targets| {
+                            for name in &targets {
+                                update.validate(&source.charge_thresholds(name)?)?;
+                            }
+                            targets
+                                .iter()
+                                .map(|name| source.set_charge_thresholds(name, &update))
+                                .collect()
+                        })
+                    }
+                    _ => {
+                        send_error(request, &cors_headers, 405, t.method_not_allowed);
+                        continue;
+                    }
+                };
+
+                match result {
+                    Ok(thresholds) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "thresholds": thresholds
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(e) => send_battery_error(request, &cors_headers, &e),
                 }
             }
             "/api/v1/battery/history" => {
                 let hours = parse_hours(query).unwrap_or(24);
-                
-                match db.get_history(hours) {
+                let battery = parse_param(query, "battery");
+
+                match db.get_history(hours, battery) {
                     Ok(history) => {
                         let json = serde_json::json!({
                             "status": "ok",
                             "data": {
                                 "hours": hours,
+                                "battery": battery,
                                 "records": history
                             }
                         });
@@ -119,19 +333,21 @@ pub fn start_server() {
                         let _ = request.respond(response);
                     }
                     Err(_) => {
-                        send_error(request, &cors_headers, 500, "Error al obtener historial");
+                        send_error(request, &cors_headers, 500, t.history_failed);
                     }
                 }
             }
             "/api/v1/battery/statistics" => {
                 let hours = parse_hours(query).unwrap_or(24);
-                
-                match db.get_statistics(hours) {
+                let battery = parse_param(query, "battery");
+
+                match db.get_statistics(hours, battery) {
                     Ok(stats) => {
                         let json = serde_json::json!({
                             "status": "ok",
                             "data": {
                                 "hours": hours,
+                                "battery": battery,
                                 "statistics": stats
                             }
                         });
@@ -142,14 +358,37 @@ pub fn start_server() {
                         let _ = request.respond(response);
                     }
                     Err(_) => {
-                        send_error(request, &cors_headers, 500, "Error al obtener estadísticas");
+                        send_error(request, &cors_headers, 500, t.statistics_failed);
+                    }
+                }
+            }
+            "/api/v1/events" => {
+                let hours = parse_hours(query).unwrap_or(24);
+
+                match db.get_events(hours) {
+                    Ok(events) => {
+                        let json = serde_json::json!({
+                            "status": "ok",
+                            "data": {
+                                "hours": hours,
+                                "events": events
+                            }
+                        });
+
+                        let response = Response::from_string(json.to_string())
+                            .with_header(cors_headers[0].clone())
+                            .with_header(cors_headers[2].clone());
+                        let _ = request.respond(response);
+                    }
+                    Err(_) => {
+                        send_error(request, &cors_headers, 500, t.events_failed);
                     }
                 }
             }
             _ => {
                 let json = serde_json::json!({
                     "status": "error",
-                    "message": "Ruta no encontrada"
+                    "message": t.route_not_found
                 });
                 let response = Response::from_string(json.to_string())
                     .with_status_code(404)
@@ -173,9 +412,89 @@ fn send_error(request: tiny_http::Request, cors_headers: &[Header], code: u16, m
     let _ = request.respond(response);
 }
 
-fn parse_hours(query: &str) -> Option<i64> {
+fn send_battery_error(request: tiny_http::Request, cors_headers: &[Header], error: &BatteryError) {
+    let json = serde_json::json!({
+        "status": "error",
+        "message": error.to_string(),
+        "error": error
+    });
+    let response = Response::from_string(json.to_string())
+        .with_status_code(error.http_status())
+        .with_header(cors_headers[0].clone())
+        .with_header(cors_headers[2].clone());
+    let _ = request.respond(response);
+}
+
+// La escritura solo se habilita si se define RUSTTERY_API_TOKEN, y la
+// petición debe incluir "Authorization: Bearer <token>".
+fn check_token(request: &tiny_http::Request, token: Option<&str>) -> Result<(), (u16, &'static str)> {
+    let Some(token) = token else {
+        return Err((403, messages().writes_disabled));
+    };
+
+    let provided = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if tokens_match(provided.as_bytes(), token.as_bytes()) => Ok(()),
+        _ => Err((401, messages().invalid_token)),
+    }
+}
+
+// Compara todos los bytes aunque haya diferencias, para que el tiempo de
+// respuesta no indique cuántos caracteres del token son correctos.
+fn tokens_match(provided: &[u8], token: &[u8]) -> bool {
+    provided.len() == token.len()
+        && provided
+            .iter()
+            .zip(token)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn select_battery(snapshot: &Snapshot, query: &str) -> Result<BatteryInfo, Arc<BatteryError>> {
+    snapshot.battery(parse_param(query, "battery"))
+}
+
+// Sin ninguna entrada, el error de la última lectura explica el motivo.
+fn power_sources(snapshot: &Snapshot) -> Result<&[PowerSourceInfo], Arc<BatteryError>> {
+    match (&snapshot.error, snapshot.power_sources.is_empty()) {
+        (Some(error), true) => Err(error.clone()),
+        _ => Ok(&snapshot.power_sources),
+    }
+}
+
+fn devices(snapshot: &Snapshot) -> Result<&[DeviceBatteryInfo], Arc<BatteryError>> {
+    match (&snapshot.error, snapshot.devices.is_empty()) {
+        (Some(error), true) => Err(error.clone()),
+        _ => Ok(&snapshot.devices),
+    }
+}
+
+fn parse_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
     query.split('&')
-        .find(|p| p.starts_with("hours="))
-        .and_then(|p| p.strip_prefix("hours="))
-        .and_then(|h| h.parse().ok())
+        .filter_map(|p| p.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_hours(query: &str) -> Option<i64> {
+    parse_param(query, "hours").and_then(|h| h.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_tokens() {
+        assert!(tokens_match(b"secreto", b"secreto"));
+        assert!(!tokens_match(b"secretO", b"secreto"));
+        assert!(!tokens_match(b"secret", b"secreto"));
+        assert!(!tokens_match(b"", b"secreto"));
+    }
 }
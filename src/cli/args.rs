@@ -0,0 +1,556 @@
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use clap::builder::Str;
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use rusttery::i18n::{messages, Language, Messages};
+
+use super::format::OutputFormat;
+use super::BarFormat;
+use crate::config::DedupRule;
+
+// La ayuda se escribe en inglés en los comentarios `///`, y
+// Cli::localized_command la traduce con el catálogo de mensajes.
+/// Battery monitor for Linux and Windows. Opens the GUI without a subcommand
+#[derive(Parser)]
+#[command(name = "rusttery", version)]
+pub struct Cli {
+    /// Configuration file (default ~/.config/rusttery/config.toml)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// SQLite history database (default in the user's data directory)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub db_path: Option<PathBuf>,
+
+    /// Days the history is kept (0 keeps it forever)
+    #[arg(long, global = true, value_name = "DAYS")]
+    pub retention_days: Option<u32>,
+
+    /// Language of the messages (default the one from LANG, or Spanish)
+    #[arg(long, global = true, value_name = "LANGUAGE", value_parser = parse_language)]
+    pub language: Option<Language>,
+
+    // Opciones de versiones anteriores, equivalentes a `watch`, `serve` y
+    // `json`.
+    #[arg(long, hide = true, conflicts_with_all = ["api", "json"])]
+    pub cli: bool,
+    #[arg(long, hide = true, conflicts_with = "json")]
+    pub api: bool,
+    #[arg(long, hide = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn localized_command() -> clap::Command {
+        localize(Cli::command(), messages())
+    }
+
+    pub fn try_parse_localized() -> Result<Self, clap::Error> {
+        Self::from_arg_matches(&Self::localized_command().try_get_matches()?)
+    }
+}
+
+// Los textos sin traducción se quedan en inglés.
+fn localize(command: clap::Command, t: &Messages) -> clap::Command {
+    let about = command
+        .get_about()
+        .and_then(|about| t.help(&about.to_string()));
+    let command = match about {
+        Some(about) => command.about(about),
+        None => command,
+    };
+
+    command
+        .mut_args(|arg| {
+            let help = arg.get_help().and_then(|help| t.help(&help.to_string()));
+            let arg = match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            };
+            let names: Option<Vec<Str>> = arg.get_value_names().map(|names| {
+                names
+                    .iter()
+                    .map(|name| t.value_name(name).map_or_else(|| name.clone(), Str::from))
+                    .collect()
+            });
+            match names {
+                Some(names) => arg.value_names(names),
+                None => arg,
+            }
+        })
+        .mut_subcommands(|subcommand| localize(subcommand, t))
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Opens the window with the battery data
+    Gui(MonitorArgs),
+    /// Opens a full-screen terminal dashboard with power and charge charts
+    Tui(MonitorArgs),
+    /// Shows battery changes in the terminal and stores them in the history
+    Watch(WatchArgs),
+    /// Records readings into the history in the background (systemd service)
+    Daemon(DaemonArgs),
+    /// Starts the REST API server
+    Serve(ServeArgs),
+    /// Prints the current reading and exits
+    Status(StatusArgs),
+    /// Prints the current reading as JSON and exits
+    Json,
+    /// Shows the charge history
+    History(HistoryQueryArgs),
+    /// Shows history statistics
+    Stats(HistoryQueryArgs),
+    /// Exports the history to CSV or JSON
+    Export(ExportArgs),
+    /// Continuous output for status bars (waybar, i3blocks, polybar, tmux)
+    Bar(BarArgs),
+    /// Nagios/Icinga check: one line with perfdata and exit code 0-3
+    Check(CheckArgs),
+    /// Shows or changes the charge thresholds
+    Threshold(ThresholdArgs),
+    /// Shows the effective configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Generates the completion script for the given shell
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Args, Default)]
+pub struct MonitorArgs {
+    /// Seconds between battery readings (default 3)
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+}
+
+#[derive(Args, Default)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub monitor: MonitorArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Only the combined view of all batteries
+    #[arg(long)]
+    pub combined: bool,
+}
+
+#[derive(Args)]
+pub struct StatusArgs {
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    /// Only the combined view of all batteries
+    #[arg(long)]
+    pub combined: bool,
+}
+
+#[derive(Args, Default)]
+pub struct OutputArgs {
+    /// Output format (default line in watch and status, table in history and
+    /// stats)
+    #[arg(long, short, value_name = "FORMAT", value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Comma-separated fields (name,percent,health,status,power_now,...)
+    #[arg(long, value_name = "FIELDS")]
+    pub fields: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DaemonArgs {
+    /// Readings that are stored: all (off), those that change the percentage
+    /// (percent) or those that change percentage, health or status (changes)
+    #[arg(long, value_name = "RULE", value_enum)]
+    pub dedup: Option<DedupRule>,
+
+    #[command(flatten)]
+    pub monitor: MonitorArgs,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// HTTP port of the server (default 3000)
+    #[arg(long, short, value_name = "PORT")]
+    pub port: Option<u16>,
+
+    /// Address the server listens on (default 0.0.0.0)
+    #[arg(long, value_name = "ADDRESS")]
+    pub bind: Option<String>,
+
+    #[command(flatten)]
+    pub monitor: MonitorArgs,
+}
+
+#[derive(Args)]
+pub struct HistoryArgs {
+    /// Hours back from now (default 24)
+    #[arg(long, value_name = "HOURS", conflicts_with = "since", value_parser = clap::value_parser!(i64).range(1..))]
+    pub hours: Option<i64>,
+
+    /// Since when: how long ago (30m, 12h, 2d, 1w, 1d12h) or a date
+    /// ("2026-10-01", "2026-10-01 08:00")
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    pub since: Option<i64>,
+
+    /// Until when, in the same format as --since (default now)
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    pub until: Option<i64>,
+
+    /// Only the records of this battery (BAT0, BAT1, ...)
+    #[arg(long, short, value_name = "NAME")]
+    pub battery: Option<String>,
+}
+
+#[derive(Args)]
+pub struct HistoryQueryArgs {
+    #[command(flatten)]
+    pub history: HistoryArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub history: HistoryArgs,
+
+    /// Format of the exported file
+    #[arg(long, short, value_name = "FORMAT", value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+
+    /// Destination file (default the standard output)
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Args)]
+pub struct BarArgs {
+    /// Target bar
+    #[arg(long, short, value_name = "FORMAT", value_enum, default_value_t = BarFormat::Waybar)]
+    pub format: BarFormat,
+
+    /// Text with placeholders such as {percent}, {power:.1}, {time_to_empty} or
+    /// {icon}
+    #[arg(
+        long,
+        short,
+        value_name = "TEMPLATE",
+        default_value = "{icon} {percent}%"
+    )]
+    pub template: String,
+
+    /// Tooltip template (waybar only)
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        default_value = "{name}: {percent}% ({status})"
+    )]
+    pub tooltip: String,
+
+    /// Comma-separated {icon} icons, from lowest to highest charge (default the
+    /// Font Awesome ones)
+    #[arg(
+        long,
+        value_name = "ICONS",
+        default_value = "\u{f244},\u{f243},\u{f242},\u{f241},\u{f240}"
+    )]
+    pub icons: String,
+
+    /// {icon} icon while charging (empty to use the level one)
+    #[arg(long, value_name = "ICON", default_value = "\u{f0e7}")]
+    pub charging_icon: String,
+
+    /// Below this percentage it is flagged as a warning without a charger
+    #[arg(long, value_name = "PERCENT", default_value_t = 30, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub warning: u8,
+
+    /// Below this percentage it is flagged as critical without a charger
+    #[arg(long, value_name = "PERCENT", default_value_t = 15, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub critical: u8,
+
+    /// Battery to show (default the combined view)
+    #[arg(long, short, value_name = "NAME")]
+    pub battery: Option<String>,
+
+    /// Prints a single line and exits (tmux, i3blocks with interval)
+    #[arg(long)]
+    pub once: bool,
+
+    #[command(flatten)]
+    pub monitor: MonitorArgs,
+}
+
+#[derive(Args)]
+pub struct CheckArgs {
+    /// Battery to check (default the combined view)
+    #[arg(long, short, value_name = "NAME")]
+    pub battery: Option<String>,
+
+    /// WARNING if the charge drops below this percentage while unplugged
+    #[arg(long, value_name = "PERCENT", default_value_t = 20, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub warn_percent: u8,
+
+    /// CRITICAL if the charge drops below this percentage while unplugged
+    #[arg(long, value_name = "PERCENT", default_value_t = 10, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub crit_percent: u8,
+
+    /// WARNING if the health drops below this percentage
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub warn_health: Option<u8>,
+
+    /// CRITICAL if the health drops below this percentage
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub crit_health: Option<u8>,
+
+    /// WARNING if it discharges faster than these points per hour (uses the
+    /// history)
+    #[arg(long, value_name = "POINTS")]
+    pub warn_rate: Option<f64>,
+
+    /// CRITICAL if it discharges faster than these points per hour (uses the
+    /// history)
+    #[arg(long, value_name = "POINTS")]
+    pub crit_rate: Option<f64>,
+
+    /// Minutes of history used to compute the discharge rate
+    #[arg(long, value_name = "MINUTES", default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate_window: u32,
+}
+
+#[derive(Args)]
+pub struct ThresholdArgs {
+    /// Battery to show or change (default all)
+    #[arg(long, short, global = true, value_name = "NAME")]
+    pub battery: Option<String>,
+
+    #[command(subcommand)]
+    pub action: Option<ThresholdAction>,
+}
+
+#[derive(Subcommand)]
+pub enum ThresholdAction {
+    /// Shows the current thresholds
+    Show,
+    /// Changes the thresholds (usually requires sudo)
+    Set {
+        /// Percentage below which charging starts
+        #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+        start: Option<u8>,
+
+        /// Percentage at which charging stops
+        #[arg(long, alias = "end", value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+        stop: Option<u8>,
+
+        /// Charge mode of charge_behaviour (auto, inhibit-charge,
+        /// force-discharge)
+        #[arg(long, alias = "behavior", value_name = "MODE")]
+        behaviour: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Prints the merged configuration and where each value comes from
+    Show,
+}
+
+// Momento como marca de tiempo Unix: una duración hacia atrás desde ahora
+// ("90m", "2d", "1d12h") o una fecha en hora local.
+fn parse_time(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    let now = Local::now();
+
+    if value == "now" || value == "ahora" {
+        return Ok(now.timestamp());
+    }
+
+    if let Some(seconds) = parse_duration(value) {
+        return Ok(now.timestamp() - seconds);
+    }
+
+    let naive = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+    });
+
+    naive
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|time| time.timestamp())
+        .ok_or_else(|| messages().invalid_time.to_string())
+}
+
+fn parse_duration(value: &str) -> Option<i64> {
+    if value.is_empty() {
+        return None;
+    }
+
+    let mut total: i64 = 0;
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if digits == 0 {
+            return None;
+        }
+        let amount: i64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+
+        let unit = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let seconds = match &rest[..unit] {
+            "s" => 1,
+            "m" | "min" => 60,
+            "h" => 3600,
+            "d" => 86400,
+            "w" => 7 * 86400,
+            _ => return None,
+        };
+        rest = &rest[unit..];
+
+        total = total.checked_add(amount.checked_mul(seconds)?)?;
+    }
+
+    Some(total)
+}
+
+fn parse_language(value: &str) -> Result<Language, String> {
+    value
+        .parse()
+        .map_err(|_| messages().invalid_language.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Textos de ayuda (sin traducir) de todas las órdenes y opciones.
+    fn help_texts(command: &clap::Command, texts: &mut Vec<String>) {
+        texts.extend(command.get_about().map(ToString::to_string));
+        for arg in command.get_arguments() {
+            texts.extend(arg.get_help().map(ToString::to_string));
+        }
+        for subcommand in command.get_subcommands() {
+            help_texts(subcommand, texts);
+        }
+    }
+
+    fn value_names(command: &clap::Command, names: &mut Vec<String>) {
+        for arg in command
+            .get_arguments()
+            .filter(|arg| arg.get_action().takes_values())
+        {
+            for name in arg.get_value_names().unwrap_or_default() {
+                names.push(name.to_string());
+            }
+        }
+        for subcommand in command.get_subcommands() {
+            value_names(subcommand, names);
+        }
+    }
+
+    fn missing_help(command: &clap::Command, path: &str, missing: &mut Vec<String>) {
+        if command.get_about().is_none() {
+            missing.push(path.to_string());
+        }
+        for arg in command.get_arguments() {
+            if arg.get_long().is_some() && !arg.is_hide_set() && arg.get_help().is_none() {
+                missing.push(format!("{} {}", path, arg.get_id()));
+            }
+        }
+        for subcommand in command.get_subcommands() {
+            let path = format!("{} {}", path, subcommand.get_name());
+            missing_help(subcommand, &path, missing);
+        }
+    }
+
+    #[test]
+    fn every_option_has_help() {
+        let mut missing = Vec::new();
+        missing_help(&Cli::command(), "rusttery", &mut missing);
+        assert!(missing.is_empty(), "{:?}", missing);
+    }
+
+    // Cada texto tiene traducción y cada traducción corresponde a un texto
+    // que sigue existiendo.
+    #[test]
+    fn spanish_help_matches_the_commands() {
+        let t = Language::Es.messages();
+        let command = Cli::command();
+
+        let mut texts = Vec::new();
+        help_texts(&command, &mut texts);
+        let untranslated: Vec<&String> =
+            texts.iter().filter(|text| t.help(text).is_none()).collect();
+        assert!(untranslated.is_empty(), "sin traducir: {:?}", untranslated);
+        let stale: Vec<&str> = t
+            .help
+            .iter()
+            .map(|(text, _)| *text)
+            .filter(|text| !texts.iter().any(|used| used == text))
+            .collect();
+        assert!(stale.is_empty(), "sobrantes: {:?}", stale);
+
+        let mut names = Vec::new();
+        value_names(&command, &mut names);
+        let untranslated: Vec<&String> = names
+            .iter()
+            .filter(|name| t.value_name(name).is_none())
+            .collect();
+        assert!(untranslated.is_empty(), "sin traducir: {:?}", untranslated);
+        let stale: Vec<&str> = t
+            .value_names
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| !names.iter().any(|used| used == name))
+            .collect();
+        assert!(stale.is_empty(), "sobrantes: {:?}", stale);
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("45s"), Some(45));
+        assert_eq!(parse_duration("90m"), Some(90 * 60));
+        assert_eq!(parse_duration("15min"), Some(15 * 60));
+        assert_eq!(parse_duration("12h"), Some(12 * 3600));
+        assert_eq!(parse_duration("1d12h"), Some(36 * 3600));
+        assert_eq!(parse_duration("1w"), Some(7 * 86400));
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        for value in ["", "h", "12", "12x", "1d 12h", "-1h", "99999999999999w"] {
+            assert_eq!(parse_duration(value), None, "{}", value);
+        }
+    }
+}
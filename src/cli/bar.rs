@@ -0,0 +1,397 @@
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use rusttery::core::{BatteryError, BatteryInfo, BatteryMonitor, Snapshot};
+use rusttery::i18n::messages;
+use serde_json::{json, Map, Value};
+
+use super::args::BarArgs;
+use super::format::{field_keys, to_map, RecordKind};
+use super::on_ac;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BarFormat {
+    Waybar,
+    I3blocks,
+    Polybar,
+    Tmux,
+}
+
+// Colores de los niveles de aviso en i3blocks, polybar y tmux.
+const WARNING_COLOR: &str = "#FFB52A";
+const CRITICAL_COLOR: &str = "#FF5555";
+
+// Nombres cortos aceptados en las plantillas además de las claves del JSON.
+const ALIASES: &[(&str, &str)] = &[
+    ("power", "power_now"),
+    ("voltage", "voltage_now"),
+    ("current", "current_now"),
+];
+
+// Valores calculados que no están en el JSON de la batería.
+const COMPUTED: &[&str] = &["icon", "time"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Normal,
+    Warning,
+    Critical,
+}
+
+// Salida para barras de estado: una línea por cambio, o una sola con
+// --once. El texto sale de una plantilla como "{icon} {percent}%".
+pub fn run_bar(monitor: &BatteryMonitor, args: &BarArgs) -> i32 {
+    let text = match Template::parse(&args.template) {
+        Ok(template) => template,
+        Err(e) => return usage_error(&e),
+    };
+    let tooltip = match Template::parse(&args.tooltip) {
+        Ok(template) => template,
+        Err(e) => return usage_error(&e),
+    };
+    let icons: Vec<&str> = args.icons.split(',').map(str::trim).collect();
+
+    let bar = Bar {
+        args,
+        text,
+        tooltip,
+        icons,
+    };
+
+    let snapshots = monitor.subscribe();
+    let mut last: Option<String> = None;
+    let mut snapshot = monitor.latest();
+    loop {
+        let line = bar.render(&snapshot);
+        if last.as_ref() != Some(&line) {
+            let mut out = io::stdout().lock();
+            // Con la barra cerrada no tiene sentido seguir.
+            if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+                return 0;
+            }
+            last = Some(line);
+        }
+
+        if args.once {
+            return 0;
+        }
+
+        snapshot = match snapshots.recv() {
+            Ok(snapshot) => snapshot,
+            Err(_) => return 0,
+        };
+    }
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("{}", message);
+    2
+}
+
+struct Bar<'a> {
+    args: &'a BarArgs,
+    text: Template,
+    tooltip: Template,
+    icons: Vec<&'a str>,
+}
+
+impl Bar<'_> {
+    fn render(&self, snapshot: &Snapshot) -> String {
+        let info = match snapshot.battery(self.args.battery.as_deref()) {
+            Ok(info) => info,
+            Err(e) => return self.render_error(&e),
+        };
+
+        let level = self.level(&info);
+        let values = self.values(&info);
+        let text = self.text.render(&values);
+
+        match self.args.format {
+            BarFormat::Waybar => {
+                let mut class = vec![status_class(&info)];
+                match level {
+                    Level::Warning => class.push("warning".to_string()),
+                    Level::Critical => class.push("critical".to_string()),
+                    Level::Normal => {}
+                }
+                json!({
+                    "text": text,
+                    "alt": class[0],
+                    "tooltip": self.tooltip.render(&values),
+                    "class": class,
+                    "percentage": info.percent,
+                })
+                .to_string()
+            }
+            BarFormat::I3blocks => {
+                let mut block = json!({
+                    "full_text": text,
+                    "short_text": format!("{}%", info.percent),
+                });
+                if let Some(color) = color(level) {
+                    block["color"] = json!(color);
+                }
+                block.to_string()
+            }
+            BarFormat::Polybar => match color(level) {
+                Some(color) => format!("%{{F{}}}{}%{{F-}}", color, text),
+                None => text,
+            },
+            BarFormat::Tmux => match color(level) {
+                Some(color) => format!("#[fg={}]{}#[default]", color, text),
+                None => text,
+            },
+        }
+    }
+
+    // Sin batería la barra queda vacía; waybar muestra el motivo en la
+    // descripción emergente.
+    fn render_error(&self, error: &BatteryError) -> String {
+        match self.args.format {
+            BarFormat::Waybar => json!({
+                "text": "",
+                "tooltip": error.to_string(),
+                "class": ["error"],
+            })
+            .to_string(),
+            BarFormat::I3blocks => json!({ "full_text": "" }).to_string(),
+            BarFormat::Polybar | BarFormat::Tmux => String::new(),
+        }
+    }
+
+    // Los avisos por carga baja solo cuentan sin cargador y, como en
+    // `check`, empiezan por debajo del umbral.
+    fn level(&self, info: &BatteryInfo) -> Level {
+        if on_ac(info) {
+            Level::Normal
+        } else if info.percent < self.args.critical.into() {
+            Level::Critical
+        } else if info.percent < self.args.warning.into() {
+            Level::Warning
+        } else {
+            Level::Normal
+        }
+    }
+
+    fn values(&self, info: &BatteryInfo) -> Map<String, Value> {
+        let mut values = to_map(info);
+        for (alias, key) in ALIASES {
+            let value = values.get(*key).cloned().unwrap_or(Value::Null);
+            values.insert(alias.to_string(), value);
+        }
+
+        let time = info.time_to_empty.or(info.time_to_full);
+        values.insert("time".to_string(), json!(time));
+        values.insert("icon".to_string(), json!(self.icon(info)));
+        values
+    }
+
+    // Los iconos se reparten a partes iguales entre 0 y 100 %; al cargar se
+    // usa --charging-icon si no está vacío.
+    fn icon(&self, info: &BatteryInfo) -> String {
+        if info.status.as_deref() == Some("Charging") && !self.args.charging_icon.is_empty() {
+            return self.args.charging_icon.clone();
+        }
+
+        let percent = info.percent.clamp(0, 100) as usize;
+        let index = (percent * self.icons.len() / 100).min(self.icons.len() - 1);
+        self.icons[index].to_string()
+    }
+}
+
+fn status_class(info: &BatteryInfo) -> String {
+    info.status
+        .as_deref()
+        .unwrap_or("unknown")
+        .to_lowercase()
+        .replace(' ', "-")
+}
+
+fn color(level: Level) -> Option<&'static str> {
+    match level {
+        Level::Normal => None,
+        Level::Warning => Some(WARNING_COLOR),
+        Level::Critical => Some(CRITICAL_COLOR),
+    }
+}
+
+enum Piece {
+    Text(String),
+    Value {
+        key: String,
+        decimals: Option<usize>,
+    },
+}
+
+// Plantilla con marcadores {clave} o {clave:.N}; "{{" y "}}" escriben las
+// llaves literales.
+struct Template(Vec<Piece>);
+
+impl Template {
+    fn parse(template: &str) -> Result<Self, String> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut spec = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => spec.push(c),
+                            None => return Err((messages().template_unclosed)(template)),
+                        }
+                    }
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(placeholder(&spec)?);
+                }
+                '}' => return Err((messages().template_unopened)(template)),
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Self(pieces))
+    }
+
+    fn render(&self, values: &Map<String, Value>) -> String {
+        let mut out = String::new();
+        for piece in &self.0 {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Value { key, decimals } => {
+                    let value = values.get(key).unwrap_or(&Value::Null);
+                    out.push_str(&render_value(key, value, *decimals));
+                }
+            }
+        }
+        out.trim().to_string()
+    }
+}
+
+fn placeholder(spec: &str) -> Result<Piece, String> {
+    let (key, format) = match spec.split_once(':') {
+        Some((key, format)) => (key.trim(), Some(format.trim())),
+        None => (spec.trim(), None),
+    };
+
+    let known = || {
+        field_keys(RecordKind::Battery)
+            .filter(|k| *k != "timestamp")
+            .chain(ALIASES.iter().map(|(alias, _)| *alias))
+            .chain(COMPUTED.iter().copied())
+    };
+    if !known().any(|k| k == key) {
+        let list: Vec<&str> = known().collect();
+        return Err((messages().template_unknown_field)(key, &list.join(", ")));
+    }
+
+    let decimals = match format {
+        None => None,
+        Some(format) => match format.strip_prefix('.').and_then(|n| n.parse().ok()) {
+            Some(decimals) => Some(decimals),
+            None => return Err((messages().template_invalid_format)(spec, key)),
+        },
+    };
+
+    Ok(Piece::Value {
+        key: key.to_string(),
+        decimals,
+    })
+}
+
+// Los campos vacíos no escriben nada; los tiempos, en minutos, salen como
+// "H:MM".
+fn render_value(key: &str, value: &Value, decimals: Option<usize>) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Bool(b) => {
+            let t = messages();
+            if *b { t.yes } else { t.no }.to_string()
+        }
+        Value::String(s) => s.clone(),
+        Value::Number(n) if matches!(key, "time" | "time_to_empty" | "time_to_full") => {
+            let minutes = n.as_i64().unwrap_or(0);
+            format!("{}:{:02}", minutes / 60, minutes % 60)
+        }
+        Value::Number(n) => match (decimals, n.as_f64()) {
+            (Some(decimals), Some(x)) => format!("{:.*}", decimals, x),
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::cli::args::{Cli, Command};
+
+    #[test]
+    fn parses_and_renders_template() {
+        let template = Template::parse("{percent}% {{ok}} {power:.1} W {time}").unwrap();
+        let values = json!({ "percent": 80, "power": 12.345, "time": 95 });
+
+        assert_eq!(
+            template.render(values.as_object().unwrap()),
+            "80% {ok} 12.3 W 1:35"
+        );
+    }
+
+    #[test]
+    fn missing_values_render_empty() {
+        let template = Template::parse("{icon} {percent}%").unwrap();
+        let values = json!({ "percent": 5 });
+
+        assert_eq!(template.render(values.as_object().unwrap()), "5%");
+    }
+
+    #[test]
+    fn levels_match_check_thresholds() {
+        let cli = Cli::try_parse_from(["rusttery", "bar", "--warning", "20", "--critical", "10"]);
+        let Some(Command::Bar(args)) = cli.unwrap().command else {
+            unreachable!();
+        };
+        let bar = Bar {
+            args: &args,
+            text: Template::parse("").unwrap(),
+            tooltip: Template::parse("").unwrap(),
+            icons: vec![""],
+        };
+        let level = |percent: i32, status: &str| {
+            let mut info = BatteryInfo::new("BAT0", percent);
+            info.status = Some(status.to_string());
+            bar.level(&info)
+        };
+
+        assert_eq!(level(21, "Discharging"), Level::Normal);
+        assert_eq!(level(20, "Discharging"), Level::Normal);
+        assert_eq!(level(19, "Discharging"), Level::Warning);
+        assert_eq!(level(10, "Discharging"), Level::Warning);
+        assert_eq!(level(9, "Discharging"), Level::Critical);
+        assert_eq!(level(9, "Charging"), Level::Normal);
+    }
+
+    #[test]
+    fn rejects_bad_templates() {
+        for template in ["{percent", "percent}", "{nope}", "{power:1}", "{power:.x}"] {
+            assert!(Template::parse(template).is_err(), "{}", template);
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+
+use rusttery::core::{combine_batteries, BatteryError, BatteryInfo, BatterySource};
+use rusttery::database::HistoryRecord;
+use rusttery::i18n::messages;
+
+use super::args::CheckArgs;
+use super::{now, on_ac};
+use crate::config::Config;
+
+// Estados y códigos de salida de los plugins de Nagios/Icinga
+// (monitoring-plugins).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ok = 0,
+    Warning = 1,
+    Critical = 2,
+    Unknown = 3,
+}
+
+impl State {
+    // El código de salida no sirve para comparar: un dato que falta
+    // (UNKNOWN) no debe ocultar un problema confirmado.
+    fn severity(self) -> u8 {
+        match self {
+            State::Ok => 0,
+            State::Unknown => 1,
+            State::Warning => 2,
+            State::Critical => 3,
+        }
+    }
+
+    fn worst(self, other: State) -> State {
+        if other.severity() > self.severity() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            State::Ok => write!(f, "OK"),
+            State::Warning => write!(f, "WARNING"),
+            State::Critical => write!(f, "CRITICAL"),
+            State::Unknown => write!(f, "UNKNOWN"),
+        }
+    }
+}
+
+// Imprime una sola línea "BATTERY <ESTADO> - <texto> | <perfdata>" y
+// devuelve el código de salida correspondiente.
+pub fn run_check(source: &dyn BatterySource, config: &Config, args: &CheckArgs) -> i32 {
+    let (state, text, perfdata) = match check(source, config, args) {
+        Ok(result) => result,
+        Err(message) => (State::Unknown, message, Vec::new()),
+    };
+
+    if perfdata.is_empty() {
+        println!("BATTERY {} - {}", state, text);
+    } else {
+        println!("BATTERY {} - {} | {}", state, text, perfdata.join(" "));
+    }
+    state as i32
+}
+
+fn check(
+    source: &dyn BatterySource,
+    config: &Config,
+    args: &CheckArgs,
+) -> Result<(State, String, Vec<String>), String> {
+    let batteries = match source.batteries() {
+        Ok(batteries) if !batteries.is_empty() => batteries,
+        Ok(_) => return Err(read_error(&BatteryError::NoBattery)),
+        Err(e) => return Err(read_error(&e)),
+    };
+
+    let info = match &args.battery {
+        Some(name) => batteries
+            .iter()
+            .find(|b| &b.name == name)
+            .cloned()
+            .ok_or_else(|| read_error(&BatteryError::UnknownBattery(name.clone())))?,
+        None => {
+            combine_batteries(&batteries).ok_or_else(|| read_error(&BatteryError::NoBattery))?
+        }
+    };
+
+    let t = messages();
+    let mut state = State::Ok;
+    let mut problems = Vec::new();
+    let mut summary = vec![match &info.status {
+        Some(status) => format!("{} {}% ({})", info.name, info.percent, status),
+        None => format!("{} {}%", info.name, info.percent),
+    }];
+    let mut perfdata = vec![format!(
+        "percent={}%;{}:;{}:;0;100",
+        info.percent, args.warn_percent, args.crit_percent
+    )];
+
+    if !on_ac(&info) {
+        if let Some(level) = below(
+            info.percent,
+            Some(args.warn_percent),
+            Some(args.crit_percent),
+        ) {
+            state = state.worst(level);
+            let limit = limit(level, args.warn_percent, args.crit_percent);
+            problems.push((t.check_percent_below)(limit));
+        }
+    }
+
+    if let Some(health) = info.health {
+        summary.push((t.check_health)(health));
+        perfdata.push(format!(
+            "health={}%;{};{};0;100",
+            health,
+            args.warn_health
+                .map(|w| format!("{}:", w))
+                .unwrap_or_default(),
+            args.crit_health
+                .map(|c| format!("{}:", c))
+                .unwrap_or_default()
+        ));
+        if let Some(level) = below(health, args.warn_health, args.crit_health) {
+            state = state.worst(level);
+            let limit = limit(level, args.warn_health, args.crit_health);
+            problems.push((t.check_health_below)(limit.unwrap_or_default()));
+        }
+    } else if args.warn_health.is_some() || args.crit_health.is_some() {
+        state = state.worst(State::Unknown);
+        problems.push(t.check_no_health.to_string());
+    }
+
+    if let Some(power) = info.power_now {
+        perfdata.push(format!("power={:.2}W;;;0", power));
+    }
+
+    if args.warn_rate.is_some() || args.crit_rate.is_some() {
+        match discharge_rate(config, args, &info, &batteries) {
+            Ok(Some(rate)) => {
+                summary.push((t.check_discharge)(rate));
+                perfdata.push(format!(
+                    "discharge_rate={:.2};{};{}",
+                    rate,
+                    args.warn_rate.map(|w| w.to_string()).unwrap_or_default(),
+                    args.crit_rate.map(|c| c.to_string()).unwrap_or_default()
+                ));
+                if let Some(level) = above(rate, args.warn_rate, args.crit_rate) {
+                    state = state.worst(level);
+                    let limit = limit(level, args.warn_rate, args.crit_rate);
+                    problems.push((t.check_discharge_above)(limit.unwrap_or_default()));
+                }
+            }
+            // Sin lecturas suficientes no se puede decir nada de la
+            // velocidad, pero tampoco es un fallo del resto de la comprobación.
+            Ok(None) => summary.push(t.check_no_history.to_string()),
+            Err(message) => {
+                state = state.worst(State::Unknown);
+                problems.push(message);
+            }
+        }
+    }
+
+    let mut text = summary.join(", ");
+    if !problems.is_empty() {
+        text = format!("{}: {}", text, problems.join("; "));
+    }
+    Ok((state, text, perfdata))
+}
+
+fn read_error(error: &BatteryError) -> String {
+    format!("{}: {}", messages().read_error, error)
+}
+
+fn below(value: i32, warn: Option<u8>, crit: Option<u8>) -> Option<State> {
+    if crit.is_some_and(|c| value < c.into()) {
+        Some(State::Critical)
+    } else if warn.is_some_and(|w| value < w.into()) {
+        Some(State::Warning)
+    } else {
+        None
+    }
+}
+
+fn above(value: f64, warn: Option<f64>, crit: Option<f64>) -> Option<State> {
+    if crit.is_some_and(|c| value > c) {
+        Some(State::Critical)
+    } else if warn.is_some_and(|w| value > w) {
+        Some(State::Warning)
+    } else {
+        None
+    }
+}
+
+fn limit<T>(level: State, warn: T, crit: T) -> T {
+    match level {
+        State::Critical => crit,
+        _ => warn,
+    }
+}
+
+// Puntos porcentuales por hora que ha perdido la batería en la ventana
+// indicada, según el historial (negativo si ha cargado). En la vista
+// combinada cuenta la batería que se descarga más deprisa.
+fn discharge_rate(
+    config: &Config,
+    args: &CheckArgs,
+    info: &BatteryInfo,
+    batteries: &[BatteryInfo],
+) -> Result<Option<f64>, String> {
+    let t = messages();
+    let db = config
+        .open_database_for_reading()
+        .map_err(|e| (t.database_open_failed)(&config.db_path.value, &e))?;
+
+    let names: Vec<&str> = match &args.battery {
+        Some(_) => vec![info.name.as_str()],
+        None => batteries.iter().map(|b| b.name.as_str()).collect(),
+    };
+
+    let since = now() - i64::from(args.rate_window) * 60;
+    let mut fastest: Option<f64> = None;
+    for name in names {
+        let history = db
+            .get_history_range(since, None, Some(name))
+            .map_err(|e| format!("{}: {}", t.history_failed, e))?;
+
+        let (Some(first), Some(last)) = (history.first(), history.last()) else {
+            continue;
+        };
+        // Con menos de un minuto de diferencia la estimación no es fiable.
+        let hours = (last.timestamp - first.timestamp) as f64 / 3600.0;
+        if hours < 1.0 / 60.0 {
+            continue;
+        }
+
+        let level = |r: &HistoryRecord| r.percent_exact.map(f64::from).unwrap_or(r.percent as f64);
+        let rate = (level(first) - level(last)) / hours;
+        fastest = Some(fastest.map_or(rate, |f| f.max(rate)));
+    }
+
+    Ok(fastest)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use rusttery::core::MockSource;
+
+    use super::*;
+    use crate::cli::args::{Cli, Command};
+    use crate::config::Overrides;
+
+    // Comprobación contra baterías simuladas y una base de datos que no
+    // existe, de modo que la velocidad de descarga siempre es UNKNOWN.
+    fn run(spec: &str, options: &[&str]) -> State {
+        let dir = tempfile::tempdir().unwrap();
+        let cli = Cli::try_parse_from(["rusttery", "check"].iter().chain(options)).unwrap();
+        let Some(Command::Check(args)) = cli.command else {
+            unreachable!();
+        };
+        let overrides = Overrides {
+            db_path: Some(dir.path().join("missing.db")),
+            ..Overrides::default()
+        };
+        let config = Config::load(Some(&dir.path().join("config.toml")), overrides).unwrap();
+        let source = MockSource::from_spec(spec).unwrap();
+
+        check(&source, &config, &args).unwrap().0
+    }
+
+    #[test]
+    fn percent_thresholds() {
+        assert_eq!(run("BAT0=50:Discharging", &[]), State::Ok);
+        assert_eq!(run("BAT0=15:Discharging", &[]), State::Warning);
+        assert_eq!(run("BAT0=5:Discharging", &[]), State::Critical);
+        assert_eq!(run("BAT0=5:Charging", &[]), State::Ok);
+    }
+
+    #[test]
+    fn unknown_does_not_hide_problems() {
+        assert_eq!(
+            run("BAT0=50:Discharging", &["--warn-health", "70"]),
+            State::Unknown
+        );
+        assert_eq!(
+            run("BAT0=5:Discharging", &["--warn-health", "70"]),
+            State::Critical
+        );
+        assert_eq!(
+            run("BAT0=15:Discharging", &["--crit-rate", "5"]),
+            State::Warning
+        );
+        assert_eq!(
+            run("BAT0=5:Discharging", &["--crit-rate", "5"]),
+            State::Critical
+        );
+    }
+}
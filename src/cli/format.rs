@@ -0,0 +1,505 @@
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use rusttery::core::BatteryInfo;
+use rusttery::i18n::{messages, Messages};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::format_timestamp;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Line,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl OutputFormat {
+    // Formatos pensados para leerse en la terminal, que en modo continuo
+    // solo imprimen las baterías que cambian.
+    pub fn is_human(self) -> bool {
+        matches!(self, OutputFormat::Table | OutputFormat::Line)
+    }
+}
+
+// Qué se imprime: lecturas de la batería (`watch`, `status`), registros del
+// historial (`history`, `export`) o estadísticas (`stats`).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    Battery,
+    History,
+    Statistics,
+}
+
+// En qué tipos de registro aparece cada campo.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    // Lecturas y registros del historial.
+    Reading,
+    // Solo lecturas en vivo.
+    Live,
+    // Historial y estadísticas.
+    Stored,
+    // Solo estadísticas.
+    Stats,
+}
+
+impl RecordKind {
+    fn includes(self, scope: Scope) -> bool {
+        match self {
+            RecordKind::Battery => matches!(scope, Scope::Reading | Scope::Live),
+            RecordKind::History => matches!(scope, Scope::Reading | Scope::Stored),
+            RecordKind::Statistics => matches!(scope, Scope::Stored | Scope::Stats),
+        }
+    }
+
+    fn default_fields(self) -> &'static [&'static str] {
+        match self {
+            RecordKind::Battery => DEFAULT_FIELDS,
+            RecordKind::History => DEFAULT_HISTORY_FIELDS,
+            RecordKind::Statistics => DEFAULT_STATISTICS_FIELDS,
+        }
+    }
+}
+
+// Campo seleccionable con --fields. La clave es la del JSON de la API; la
+// etiqueta, que sale del catálogo de mensajes, y la unidad solo se usan en
+// los formatos `line` y `table`.
+struct Field {
+    key: &'static str,
+    label: Label,
+    unit: &'static str,
+    decimals: Option<usize>,
+    scope: Scope,
+}
+
+type Label = fn(&Messages) -> &'static str;
+
+const fn field(key: &'static str, label: Label, unit: &'static str) -> Field {
+    Field {
+        key,
+        label,
+        unit,
+        decimals: None,
+        scope: Scope::Reading,
+    }
+}
+
+const fn decimal(key: &'static str, label: Label, unit: &'static str, decimals: usize) -> Field {
+    Field {
+        key,
+        label,
+        unit,
+        decimals: Some(decimals),
+        scope: Scope::Reading,
+    }
+}
+
+impl Field {
+    const fn only(self, scope: Scope) -> Field {
+        Field { scope, ..self }
+    }
+}
+
+const FIELDS: &[Field] = &[
+    field("timestamp", |t| t.time, ""),
+    field("name", |t| t.name, "").only(Scope::Live),
+    field("battery", |t| t.name, "").only(Scope::Stored),
+    field("percent", |t| t.battery, "%"),
+    decimal("percent_exact", |t| t.percent_exact, "%", 2),
+    field("health", |t| t.health, "%"),
+    field("status", |t| t.status, ""),
+    field("cycle_count", |t| t.cycles, ""),
+    decimal("voltage_now", |t| t.voltage, "V", 2),
+    decimal("current_now", |t| t.current, "A", 2),
+    decimal("power_now", |t| t.power, "W", 2),
+    field("technology", |t| t.technology, ""),
+    field("manufacturer", |t| t.manufacturer, ""),
+    field("model", |t| t.model, ""),
+    field("serial_number", |t| t.serial_number, ""),
+    field("capacity_unit", |t| t.capacity_unit, ""),
+    decimal("energy_now_wh", |t| t.energy_now, "Wh", 2),
+    decimal("energy_full_wh", |t| t.energy_full, "Wh", 2),
+    decimal("energy_full_design_wh", |t| t.energy_full_design, "Wh", 2),
+    decimal("charge_now_ah", |t| t.charge_now, "Ah", 3),
+    decimal("charge_full_ah", |t| t.charge_full, "Ah", 3),
+    decimal("charge_full_design_ah", |t| t.charge_full_design, "Ah", 3),
+    decimal("voltage_min_design", |t| t.voltage_min_design, "V", 2),
+    field("time_to_empty", |t| t.time_to_empty_short, " min"),
+    field("time_to_full", |t| t.time_to_full_short, " min"),
+    field("time_method", |t| t.estimate, ""),
+    decimal("temperature", |t| t.temperature, " °C", 1),
+    field("capacity_level", |t| t.capacity_level, ""),
+    field("alarm", |t| t.alarm, ""),
+    field("present", |t| t.present, ""),
+    field("health_status", |t| t.health_status, ""),
+    field("read_method", |t| t.read_method, "").only(Scope::Live),
+    field("total_records", |t| t.records, "").only(Scope::Stats),
+    decimal("avg_percent", |t| t.avg_percent, "%", 1).only(Scope::Stats),
+    field("min_percent", |t| t.min_percent, "%").only(Scope::Stats),
+    field("max_percent", |t| t.max_percent, "%").only(Scope::Stats),
+    decimal("avg_power", |t| t.avg_power, "W", 2).only(Scope::Stats),
+];
+
+// Los mismos campos que la salida clásica de `rusttery watch`.
+const DEFAULT_FIELDS: &[&str] = &[
+    "name",
+    "percent",
+    "health",
+    "status",
+    "power_now",
+    "time_to_empty",
+    "time_to_full",
+];
+
+const DEFAULT_HISTORY_FIELDS: &[&str] = &[
+    "timestamp",
+    "battery",
+    "percent",
+    "health",
+    "status",
+    "power_now",
+];
+
+const DEFAULT_STATISTICS_FIELDS: &[&str] = &[
+    "battery",
+    "total_records",
+    "avg_percent",
+    "min_percent",
+    "max_percent",
+    "avg_power",
+];
+
+pub struct Printer {
+    format: OutputFormat,
+    fields: Vec<&'static Field>,
+    header_printed: bool,
+    widths: Vec<usize>,
+}
+
+impl Printer {
+    // Sin --fields, `line`, `table` y `csv` muestran los campos de la salida
+    // clásica y `json`/`ndjson` todos.
+    pub fn new(
+        format: OutputFormat,
+        fields: Option<&str>,
+        kind: RecordKind,
+    ) -> Result<Self, String> {
+        let available = || FIELDS.iter().filter(move |f| kind.includes(f.scope));
+        let fields = match fields {
+            Some(list) => list
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(|key| {
+                    available().find(|f| f.key == key).ok_or_else(|| {
+                        let known: Vec<&str> = available().map(|f| f.key).collect();
+                        (messages().unknown_field)(key, &known.join(", "))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None if matches!(format, OutputFormat::Json | OutputFormat::Ndjson) => {
+                available().collect()
+            }
+            None => available()
+                .filter(|f| kind.default_fields().contains(&f.key))
+                .collect(),
+        };
+
+        if fields.is_empty() {
+            return Err(messages().fields_required.to_string());
+        }
+
+        Ok(Self {
+            format,
+            fields,
+            header_printed: false,
+            widths: Vec::new(),
+        })
+    }
+
+    // Todos los campos en cualquier formato, como en `rusttery export`.
+    pub fn with_all_fields(format: OutputFormat, kind: RecordKind) -> Self {
+        Self {
+            format,
+            fields: FIELDS.iter().filter(|f| kind.includes(f.scope)).collect(),
+            header_printed: false,
+            widths: Vec::new(),
+        }
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format
+    }
+
+    // Imprime una lectura. En `json` es un array con todas las baterías; en
+    // el resto, una fila o línea por batería.
+    pub fn print(&mut self, timestamp: i64, batteries: &[BatteryInfo]) -> io::Result<()> {
+        let records = batteries
+            .iter()
+            .map(|info| {
+                let mut record = to_map(info);
+                record.insert("timestamp".to_string(), Value::from(timestamp));
+                record
+            })
+            .collect();
+
+        self.write_maps(&mut io::stdout().lock(), records)
+    }
+
+    // Igual que `print` con registros ya guardados, que llevan su propia hora.
+    pub fn write(&mut self, out: &mut dyn Write, records: &[impl Serialize]) -> io::Result<()> {
+        self.write_maps(out, records.iter().map(to_map).collect())
+    }
+
+    // Las cabeceras de `table` y `csv` solo se imprimen la primera vez.
+    fn write_maps(&mut self, out: &mut dyn Write, all: Vec<Map<String, Value>>) -> io::Result<()> {
+        let records: Vec<Map<String, Value>> = all
+            .into_iter()
+            .map(|mut record| {
+                self.fields
+                    .iter()
+                    .map(|f| {
+                        (
+                            f.key.to_string(),
+                            record.remove(f.key).unwrap_or(Value::Null),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        match self.format {
+            OutputFormat::Json => {
+                let array = Value::Array(records.into_iter().map(Value::Object).collect());
+                writeln!(out, "{}", serde_json::to_string_pretty(&array)?)?;
+            }
+            OutputFormat::Ndjson => {
+                for record in records {
+                    writeln!(out, "{}", Value::Object(record))?;
+                }
+            }
+            OutputFormat::Csv => {
+                if !self.header_printed {
+                    let header: Vec<&str> = self.fields.iter().map(|f| f.key).collect();
+                    writeln!(out, "{}", header.join(","))?;
+                    self.header_printed = true;
+                }
+                for record in &records {
+                    let row: Vec<String> = self
+                        .fields
+                        .iter()
+                        .map(|f| csv_field(&record[f.key]))
+                        .collect();
+                    writeln!(out, "{}", row.join(","))?;
+                }
+            }
+            OutputFormat::Table => {
+                let rows: Vec<Vec<String>> = records
+                    .iter()
+                    .map(|record| {
+                        self.fields
+                            .iter()
+                            .map(|f| display(f, &record[f.key]).unwrap_or_else(|| "-".to_string()))
+                            .collect()
+                    })
+                    .collect();
+
+                // El ancho de las columnas se fija con la primera lectura.
+                if !self.header_printed {
+                    self.widths = self
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, f)| {
+                            rows.iter()
+                                .map(|row| row[i].chars().count())
+                                .chain([(f.label)(messages()).chars().count()])
+                                .max()
+                                .unwrap_or(0)
+                        })
+                        .collect();
+                    let header: Vec<&str> =
+                        self.fields.iter().map(|f| (f.label)(messages())).collect();
+                    writeln!(out, "{}", table_row(&header, &self.widths))?;
+                    self.header_printed = true;
+                }
+
+                for row in &rows {
+                    writeln!(out, "{}", table_row(row, &self.widths))?;
+                }
+            }
+            OutputFormat::Line => {
+                for record in &records {
+                    writeln!(out, "{}", self.line(record))?;
+                }
+            }
+        }
+
+        out.flush()
+    }
+
+    // "[BAT0] Batería: 80% | Salud: 90% | Estado: Discharging"
+    fn line(&self, record: &Map<String, Value>) -> String {
+        let mut parts = Vec::new();
+        for f in &self.fields {
+            if matches!(f.key, "name" | "battery") {
+                continue;
+            }
+            if let Some(value) = display(f, &record[f.key]) {
+                parts.push(format!("{}: {}", (f.label)(messages()), value));
+            }
+        }
+
+        let name = record.get("name").or_else(|| record.get("battery"));
+        match name.and_then(Value::as_str) {
+            Some(name) if parts.is_empty() => format!("[{}]", name),
+            Some(name) => format!("[{}] {}", name, parts.join(" | ")),
+            None => parts.join(" | "),
+        }
+    }
+}
+
+// Claves de los campos de un tipo de registro, en el orden del JSON.
+pub fn field_keys(kind: RecordKind) -> impl Iterator<Item = &'static str> {
+    FIELDS
+        .iter()
+        .filter(move |f| kind.includes(f.scope))
+        .map(|f| f.key)
+}
+
+// Se pasa por el texto JSON para que los f32 salgan con el mismo redondeo
+// que en la API.
+pub fn to_map(value: &impl Serialize) -> Map<String, Value> {
+    serde_json::to_string(value)
+        .and_then(|json| serde_json::from_str(&json))
+        .unwrap_or_default()
+}
+
+fn display(field: &Field, value: &Value) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        Value::Bool(b) => {
+            let t = messages();
+            if *b { t.yes } else { t.no }.to_string()
+        }
+        Value::String(s) => s.clone(),
+        Value::Number(n) if field.key == "timestamp" => format_timestamp(n.as_i64()?),
+        Value::Number(n) => match (field.decimals, n.as_f64()) {
+            (Some(decimals), Some(x)) => format!("{:.*}", decimals, x),
+            _ => n.to_string(),
+        },
+        other => other.to_string(),
+    };
+
+    Some(format!("{}{}", text, field.unit))
+}
+
+fn table_row(cells: &[impl AsRef<str>], widths: &[usize]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell.as_ref(), width = *width))
+        .collect();
+    cells.join("  ").trim_end().to_string()
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => return String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn output(printer: &mut Printer, records: &[Value]) -> String {
+        let mut out = Vec::new();
+        printer.write(&mut out, records).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn selects_fields_in_order() {
+        let mut printer = Printer::new(
+            OutputFormat::Csv,
+            Some("percent, name"),
+            RecordKind::Battery,
+        )
+        .unwrap();
+        let records = [json!({ "name": "BAT0", "percent": 80, "health": 90 })];
+
+        assert_eq!(output(&mut printer, &records), "percent,name\n80,BAT0\n");
+        // La cabecera solo sale la primera vez.
+        assert_eq!(output(&mut printer, &records), "80,BAT0\n");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let error = Printer::new(
+            OutputFormat::Line,
+            Some("percent,nope"),
+            RecordKind::Battery,
+        );
+        assert!(error.is_err_and(|e| e.contains("nope")));
+
+        // Los campos de las estadísticas no existen en las lecturas.
+        let error = Printer::new(OutputFormat::Csv, Some("avg_percent"), RecordKind::Battery);
+        assert!(error.is_err_and(|e| e.contains("avg_percent")));
+
+        assert!(Printer::new(OutputFormat::Csv, Some(" , "), RecordKind::Battery).is_err());
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        let mut printer = Printer::new(
+            OutputFormat::Csv,
+            Some("name,status,health"),
+            RecordKind::Battery,
+        )
+        .unwrap();
+        let records = [json!({ "name": "BAT0", "status": "Not \"charging\", AC" })];
+
+        assert_eq!(
+            output(&mut printer, &records),
+            "name,status,health\nBAT0,\"Not \"\"charging\"\", AC\",\n"
+        );
+    }
+
+    #[test]
+    fn writes_one_json_object_per_line() {
+        let mut printer = Printer::new(
+            OutputFormat::Ndjson,
+            Some("name,percent"),
+            RecordKind::Battery,
+        )
+        .unwrap();
+        let records = [
+            json!({ "name": "BAT0", "percent": 80 }),
+            json!({ "name": "BAT1\nX", "percent": null }),
+        ];
+
+        let text = output(&mut printer, &records);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(text.ends_with('\n'));
+        assert_eq!(lines[0], r#"{"name":"BAT0","percent":80}"#);
+        assert_eq!(
+            serde_json::from_str::<Value>(lines[1]).unwrap(),
+            json!({ "name": "BAT1\nX", "percent": null })
+        );
+    }
+}
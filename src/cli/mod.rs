@@ -1,69 +1,592 @@
-use std::{thread, time::Duration};
-use crate::core::get_battery_info;
-use crate::database::Database;
+pub mod args;
+mod bar;
+mod check;
+mod format;
 
-pub fn run() {
-    let interval = Duration::from_secs(3);
-    let mut last_percent: Option<i32> = None;
-    let mut last_health: Option<i32> = None;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::thread;
+use chrono::{Local, TimeZone};
+use rusttery::core::{
+    combine_batteries, BatteryChange, BatteryError, BatteryInfo, BatteryMonitor, BatterySource,
+    ChangeFilter, ChargeThresholds, DeviceBatteryInfo, PowerEvent, PowerSourceInfo,
+    PowerSourceKind, ThresholdUpdate,
+};
+use rusttery::database::{Database, Statistics};
+use rusttery::i18n::messages;
+use serde::Serialize;
 
-    let db = Database::new().ok();
+use crate::config::Config;
+use args::{
+    ExportArgs, ExportFormat, HistoryArgs, HistoryQueryArgs, OutputArgs, StatusArgs,
+    ThresholdAction, ThresholdArgs, WatchArgs,
+};
+use format::{OutputFormat, Printer, RecordKind};
+
+pub use bar::{run_bar, BarFormat};
+pub use check::run_check;
+
+const WARNING_LEVELS: [i32; 3] = [20, 10, 5];
+
+pub fn run(monitor: &BatteryMonitor, config: &Config, args: &WatchArgs) -> i32 {
+    let mut printer = match printer(&args.output, OutputFormat::Line, RecordKind::Battery) {
+        Ok(printer) => printer,
+        Err(code) => return code,
+    };
+    // Las líneas de estado, eventos y avisos van a stderr con los formatos
+    // para scripts, de modo que stdout solo contiene datos.
+    let human = printer.format().is_human();
+    let classic = printer.format() == OutputFormat::Line && !args.combined;
+
+    let mut last_seen: HashMap<String, (i32, Option<i32>)> = HashMap::new();
+    let mut last_power: Option<String> = None;
+    let mut last_devices: HashMap<String, String> = HashMap::new();
+
+    let db = config.open_database().ok();
 
     #[cfg(not(any(windows, target_os = "linux")))]
     {
-        eprintln!("Error: Este sistema operativo no está soportado.");
-        eprintln!("Rusttery solo funciona en Windows y Linux.");
-        return;
+        let t = messages();
+        eprintln!("{}: {}", t.error, t.unsupported_platform);
+        eprintln!("{}", t.supported_platforms);
+        return 1;
     }
 
+    let snapshots = monitor.subscribe();
+    let warnings = monitor.subscribe_changes(ChangeFilter {
+        thresholds: WARNING_LEVELS.to_vec(),
+        ..ChangeFilter::default()
+    });
+    thread::spawn(move || {
+        for change in warnings {
+            if let BatteryChange::ThresholdCrossed { name, threshold, rising, .. } = change {
+                let t = messages();
+                let text = (t.threshold_crossed)(&name, threshold, rising);
+                notice(human, &format!("[{}] {}", t.warning, text));
+            }
+        }
+    });
+
+    let mut snapshot = monitor.latest();
     loop {
-        if let Some(info) = get_battery_info() {
-            let percent_changed = last_percent != Some(info.percent);
-            let health_changed = last_health != info.health;
-            
-            if percent_changed || health_changed {
-                print!("Batería: {}%", info.percent);
-                
-                if let Some(health) = info.health {
-                    print!(" | Salud: {}%", health);
-                }
-                
-                if let Some(status) = &info.status {
-                    print!(" | Estado: {}", status);
-                }
-                
-                if let Some(power) = info.power_now {
-                    print!(" | Potencia: {:.2}W", power);
+        for event in &snapshot.events {
+            notice(human, &event_line(event));
+            if let Some(database) = &db {
+                let _ = database.save_event(event);
+            }
+        }
+
+        let t = messages();
+        if let Some(e) = &snapshot.error {
+            notice(human, &format!("{}: {}", t.read_error, e));
+        } else if snapshot.batteries.is_empty() {
+            notice(human, &format!("{}: {}", t.read_error, BatteryError::NoBattery));
+        }
+
+        let mut changed = Vec::new();
+        for info in &snapshot.batteries {
+            let current = (info.percent, info.health);
+            if last_seen.get(&info.name) == Some(&current) {
+                continue;
+            }
+            last_seen.insert(info.name.clone(), current);
+            changed.push(info.clone());
+
+            if let Some(ref database) = db {
+                let _ = database.save_battery_info(info);
+            }
+        }
+
+        // Los formatos para terminal solo muestran lo que cambia; los demás
+        // emiten cada lectura completa.
+        let rows: Vec<BatteryInfo> = match (args.combined, human) {
+            (true, true) if changed.is_empty() => Vec::new(),
+            (true, _) => snapshot.combined.iter().cloned().collect(),
+            (false, true) => changed.clone(),
+            (false, false) => snapshot.batteries.clone(),
+        };
+        if !rows.is_empty() {
+            if let Err(e) = printer.print(now(), &rows) {
+                // Con stdout cerrado (por ejemplo, `| head`) se termina sin
+                // error; con cualquier otro fallo, con código 1.
+                return print_result(Err(e));
+            }
+        }
+
+        if classic && !changed.is_empty() && snapshot.batteries.len() > 1 {
+            if let Some(total) = &snapshot.combined {
+                print!("[{}] {}: {}%", t.total, t.battery, total.percent);
+                if let Some(health) = total.health {
+                    print!(" | {}: {}%", t.health, health);
                 }
-                
                 println!();
-                
-                last_percent = Some(info.percent);
-                last_health = info.health;
+            }
+        }
+
+        if classic {
+            let sources = &snapshot.power_sources;
+            let line = power_line(sources);
+            if !sources.is_empty() && last_power.as_ref() != Some(&line) {
+                println!("{}", line);
+                last_power = Some(line);
+            }
 
-                if let Some(ref database) = db {
-                    let _ = database.save_battery_info(&info);
+            let devices = &snapshot.devices;
+            last_devices.retain(|name, _| devices.iter().any(|d| &d.name == name));
+            for device in devices {
+                let line = device_line(device);
+                if last_devices.get(&device.name) != Some(&line) {
+                    println!("{}", line);
+                    last_devices.insert(device.name.clone(), line);
                 }
             }
-        } else {
-            println!("No se pudo obtener información de la batería.");
         }
 
-        thread::sleep(interval);
+        snapshot = match snapshots.recv() {
+            Ok(snapshot) => snapshot,
+            Err(_) => return 0,
+        };
     }
 }
 
-pub fn run_json() {
-    if let Some(info) = get_battery_info() {
-        match serde_json::to_string_pretty(&info) {
-            Ok(json) => println!("{}", json),
-            Err(_) => {
-                eprintln!("{{\"error\":\"Error al serializar datos\"}}");
-                std::process::exit(1);
-            }
+fn printer(output: &OutputArgs, default: OutputFormat, kind: RecordKind) -> Result<Printer, i32> {
+    let format = output.format.unwrap_or(default);
+    Printer::new(format, output.fields.as_deref(), kind).map_err(|e| {
+        eprintln!("{}", e);
+        2
+    })
+}
+
+// Estados en los que la batería está conectada a la corriente; entonces la
+// carga baja no es un problema.
+const ON_AC: [&str; 3] = ["Charging", "Full", "Not charging"];
+
+fn on_ac(info: &BatteryInfo) -> bool {
+    info.status.as_deref().is_some_and(|s| ON_AC.contains(&s))
+}
+
+fn notice(human: bool, line: &str) {
+    if human {
+        println!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Lectura puntual con el mismo formato que `watch`.
+pub fn run_status(source: &dyn BatterySource, args: &StatusArgs) -> i32 {
+    let mut printer = match printer(&args.output, OutputFormat::Line, RecordKind::Battery) {
+        Ok(printer) => printer,
+        Err(code) => return code,
+    };
+
+    let batteries = match source.batteries() {
+        Ok(batteries) if !batteries.is_empty() => batteries,
+        Ok(_) => return report_status_error(&printer, &BatteryError::NoBattery),
+        Err(e) => return report_status_error(&printer, &e),
+    };
+
+    let rows = match combine_batteries(&batteries) {
+        Some(total) if args.combined => vec![total],
+        _ => batteries,
+    };
+
+    print_result(printer.print(now(), &rows))
+}
+
+fn print_result(result: io::Result<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+fn report_status_error(printer: &Printer, error: &BatteryError) -> i32 {
+    if printer.format().is_human() {
+        eprintln!("{}: {}", messages().read_error, error);
+    } else {
+        eprintln!("{}", serde_json::json!({ "error": error }));
+    }
+    1
+}
+
+fn event_line(event: &PowerEvent) -> String {
+    let t = messages();
+    let text = match event {
+        PowerEvent::ChargerConnected { name } => (t.charger_connected)(name),
+        PowerEvent::ChargerDisconnected { name } => (t.charger_disconnected)(name),
+        PowerEvent::BatteryAdded { name } => (t.battery_added)(name),
+        PowerEvent::BatteryRemoved { name } => (t.battery_removed)(name),
+        PowerEvent::StatusChanged { name, status } => {
+            (t.status_changed)(name, status.as_deref().unwrap_or(t.unknown_status))
         }
+    };
+    format!("[{}] {}", t.event, text)
+}
+
+fn device_line(device: &DeviceBatteryInfo) -> String {
+    let t = messages();
+    let mut line = format!("[{}] {}: {}", device.name, t.device, device.display_name());
+
+    match (device.percent, &device.capacity_level) {
+        (Some(percent), _) => line.push_str(&format!(" | {}: {}%", t.battery, percent)),
+        (None, Some(level)) => line.push_str(&format!(" | {}: {}", t.level, level)),
+        (None, None) => {}
+    }
+
+    if let Some(status) = &device.status {
+        line.push_str(&format!(" | {}: {}", t.status, status));
+    }
+
+    line
+}
+
+fn power_line(sources: &[PowerSourceInfo]) -> String {
+    let online: Vec<String> = sources
+        .iter()
+        .filter(|s| s.online)
+        .map(describe_power_source)
+        .collect();
+
+    let t = messages();
+    if online.is_empty() {
+        format!("{}: {}", t.power_supply, t.on_battery)
     } else {
-        eprintln!("{{\"error\":\"No se pudo obtener información de la batería\"}}");
-        std::process::exit(1);
+        format!("{}: {}", t.power_supply, online.join(" | "))
+    }
+}
+
+fn describe_power_source(source: &PowerSourceInfo) -> String {
+    let mut text = match source.kind {
+        PowerSourceKind::Mains => format!("{} ({})", source.name, messages().mains),
+        PowerSourceKind::Usb => format!("{} (USB)", source.name),
+        PowerSourceKind::UsbPd => format!("{} (USB PD)", source.name),
+    };
+
+    if let Some(voltage) = source.voltage_max.or(source.voltage_now) {
+        text.push_str(&format!(" {:.1}V", voltage));
+    }
+
+    if let Some(current) = source.current_max {
+        text.push_str(&format!(" {:.2}A", current));
+    }
+
+    text
+}
+
+pub fn run_json(source: &dyn BatterySource) {
+    let result = source
+        .batteries()
+        .and_then(|batteries| match combine_batteries(&batteries) {
+            Some(info) => Ok((info, batteries)),
+            None => Err(BatteryError::NoBattery),
+        });
+
+    let (info, batteries) = match result {
+        Ok(found) => found,
+        Err(e) => {
+            eprintln!("{}", serde_json::json!({ "error": e }));
+            std::process::exit(1);
+        }
+    };
+
+    let devices = source.device_batteries().unwrap_or_default();
+
+    let output = serde_json::to_value(&info).and_then(|mut value| {
+        value["batteries"] = serde_json::to_value(&batteries)?;
+        value["devices"] = serde_json::to_value(&devices)?;
+        serde_json::to_string_pretty(&value)
+    });
+
+    match output {
+        Ok(json) => println!("{}", json),
+        Err(_) => {
+            eprintln!("{}", serde_json::json!({ "error": messages().serialize_failed }));
+            std::process::exit(1);
+        }
+    }
+}
+
+pub fn run_threshold(source: &dyn BatterySource, args: &ThresholdArgs) -> i32 {
+    let update = match &args.action {
+        Some(ThresholdAction::Set { start, stop, behaviour }) => Some(ThresholdUpdate {
+            start: *start,
+            end: *stop,
+            behaviour: behaviour.clone(),
+        }),
+        Some(ThresholdAction::Show) | None => None,
+    };
+
+    let targets = match &args.battery {
+        Some(name) => vec![name.clone()],
+        None => match source.batteries() {
+            Ok(batteries) if !batteries.is_empty() => {
+                batteries.into_iter().map(|b| b.name).collect()
+            }
+            Ok(_) => return report_threshold_error(&BatteryError::NoBattery),
+            Err(e) => return report_threshold_error(&e),
+        },
+    };
+
+    let mut exit_code = 0;
+    for name in targets {
+        let result = match &update {
+            Some(update) => source.set_charge_thresholds(&name, update),
+            None => source.charge_thresholds(&name),
+        };
+
+        match result {
+            Ok(thresholds) => println!("{}", threshold_line(&thresholds)),
+            Err(e) => exit_code = report_threshold_error(&e),
+        }
+    }
+
+    exit_code
+}
+
+fn threshold_line(thresholds: &ChargeThresholds) -> String {
+    let t = messages();
+    let mut parts = Vec::new();
+
+    if let Some(start) = thresholds.start {
+        parts.push(format!("{}: {}%", t.charge_start, start));
+    }
+
+    if let Some(end) = thresholds.end {
+        parts.push(format!("{}: {}%", t.charge_end, end));
+    }
+
+    if let Some(behaviour) = &thresholds.behaviour {
+        parts.push(format!("{}: {}", t.charge_mode, behaviour));
+    }
+
+    format!("[{}] {}", thresholds.battery, parts.join(" | "))
+}
+
+fn report_threshold_error(error: &BatteryError) -> i32 {
+    let t = messages();
+    eprintln!("{}: {}", t.error, error);
+    if error.code() == "permission_denied" {
+        eprintln!("{}", t.threshold_permission);
+    }
+    1
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+// Intervalo de --hours, --since y --until como marcas de tiempo Unix.
+fn time_range(args: &HistoryArgs) -> Result<(i64, Option<i64>), i32> {
+    let since = args
+        .since
+        .unwrap_or_else(|| now() - args.hours.unwrap_or(24) * 3600);
+
+    match args.until {
+        Some(until) if until < since => {
+            eprintln!(
+                "{}",
+                (messages().until_before_since)(
+                    &format_timestamp(until),
+                    &format_timestamp(since)
+                )
+            );
+            Err(2)
+        }
+        until => Ok((since, until)),
+    }
+}
+
+// "No hay registros de BAT0 entre 2026-10-15 08:00:00 y ahora"
+fn no_records(battery: Option<&str>, since: i64, until: Option<i64>) -> String {
+    let until = until.map(format_timestamp);
+    (messages().no_records)(battery, &format_timestamp(since), until.as_deref())
+}
+
+// Las consultas abren la base de datos sin limpiarla, así que funcionan
+// mientras `daemon`, `watch` o `serve` siguen guardando lecturas.
+fn open_for_reading(config: &Config) -> Result<Database, i32> {
+    config.open_database_for_reading().map_err(|e| {
+        eprintln!("{}", (messages().database_open_failed)(&config.db_path.value, &e));
+        1
+    })
+}
+
+pub fn run_history(config: &Config, args: &HistoryQueryArgs) -> i32 {
+    let mut printer = match printer(&args.output, OutputFormat::Table, RecordKind::History) {
+        Ok(printer) => printer,
+        Err(code) => return code,
+    };
+    let (since, until) = match time_range(&args.history) {
+        Ok(range) => range,
+        Err(code) => return code,
+    };
+    let db = match open_for_reading(config) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let battery = args.history.battery.as_deref();
+    let history = match db.get_history_range(since, until, battery) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("{}: {}", messages().history_failed, e);
+            return 1;
+        }
+    };
+
+    if history.is_empty() && printer.format().is_human() {
+        println!("{}", no_records(battery, since, until));
+        return 0;
+    }
+
+    print_result(printer.write(&mut io::stdout().lock(), &history))
+}
+
+#[derive(Serialize)]
+struct StatisticsRow {
+    battery: String,
+    #[serde(flatten)]
+    stats: Statistics,
+}
+
+// Una fila por batería y, si hay varias, otra con el total.
+pub fn run_stats(config: &Config, args: &HistoryQueryArgs) -> i32 {
+    let mut printer = match printer(&args.output, OutputFormat::Table, RecordKind::Statistics) {
+        Ok(printer) => printer,
+        Err(code) => return code,
+    };
+    let (since, until) = match time_range(&args.history) {
+        Ok(range) => range,
+        Err(code) => return code,
+    };
+    let db = match open_for_reading(config) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let battery = args.history.battery.as_deref();
+    let rows = match battery {
+        Some(name) => db
+            .get_statistics_range(since, until, Some(name))
+            .map(|stats| {
+                vec![StatisticsRow {
+                    battery: name.to_string(),
+                    stats,
+                }]
+            }),
+        None => statistics_rows(&db, since, until),
+    };
+
+    let rows = match rows {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("{}: {}", messages().statistics_failed, e);
+            return 1;
+        }
+    };
+
+    let empty = rows.iter().all(|row| row.stats.total_records == 0);
+    if empty && printer.format().is_human() {
+        println!("{}", no_records(battery, since, until));
+        return 0;
+    }
+
+    print_result(printer.write(&mut io::stdout().lock(), &rows))
+}
+
+fn statistics_rows(
+    db: &Database,
+    since: i64,
+    until: Option<i64>,
+) -> rusqlite::Result<Vec<StatisticsRow>> {
+    let names = db.get_batteries(since, until)?;
+    let mut rows = names
+        .iter()
+        .map(|name| {
+            db.get_statistics_range(since, until, Some(name))
+                .map(|stats| StatisticsRow {
+                    battery: name.clone(),
+                    stats,
+                })
+        })
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    if rows.len() != 1 {
+        rows.push(StatisticsRow {
+            battery: messages().total.to_string(),
+            stats: db.get_statistics_range(since, until, None)?,
+        });
+    }
+
+    Ok(rows)
+}
+
+pub fn run_export(config: &Config, args: &ExportArgs) -> i32 {
+    let (since, until) = match time_range(&args.history) {
+        Ok(range) => range,
+        Err(code) => return code,
+    };
+    let db = match open_for_reading(config) {
+        Ok(db) => db,
+        Err(code) => return code,
+    };
+
+    let history = match db.get_history_range(since, until, args.history.battery.as_deref()) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("{}: {}", messages().history_failed, e);
+            return 1;
+        }
+    };
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(io::BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("{}", (messages().create_failed)(path, &e));
+                return 1;
+            }
+        },
+        None => Box::new(io::stdout().lock()),
+    };
+
+    // Las mismas columnas que el JSON de la API.
+    let format = match args.format {
+        ExportFormat::Csv => OutputFormat::Csv,
+        ExportFormat::Json => OutputFormat::Json,
+    };
+    let mut printer = Printer::with_all_fields(format, RecordKind::History);
+
+    match printer
+        .write(&mut output, &history)
+        .and_then(|_| output.flush())
+    {
+        Ok(()) => {
+            if let Some(path) = &args.output {
+                eprintln!("{}", (messages().exported)(history.len(), path));
+            }
+            0
+        }
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => 0,
+        Err(e) => {
+            eprintln!("{}: {}", messages().export_failed, e);
+            1
+        }
     }
 }
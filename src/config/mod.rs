@@ -0,0 +1,421 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::ValueEnum;
+use rusttery::database::{Database, DEFAULT_RETENTION_DAYS};
+use rusttery::i18n::{self, messages, Language};
+use serde::Deserialize;
+
+// Configuración efectiva de los frontales. Cada valor se toma, de menor a
+// mayor prioridad, del valor por defecto, del archivo TOML, de su variable
+// de entorno y de la opción de la línea de comandos, y recuerda de dónde
+// salió para `rusttery config show`.
+pub struct Config {
+    pub path: PathBuf,
+    pub file_found: bool,
+    pub language: Setting<Language>,
+    pub interval: Setting<u64>,
+    pub port: Setting<u16>,
+    pub bind: Setting<String>,
+    pub db_path: Setting<PathBuf>,
+    pub retention_days: Setting<u32>,
+    pub dedup: Setting<DedupRule>,
+}
+
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    Env(&'static str),
+    Flag(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = messages();
+        match self {
+            Source::Default => write!(f, "{}", t.source_default),
+            Source::File => write!(f, "{}", t.source_file),
+            Source::Env(var) => write!(f, "{}", (t.source_env)(var)),
+            Source::Flag(flag) => write!(f, "{}", (t.source_flag)(flag)),
+        }
+    }
+}
+
+impl<T> Setting<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            source: Source::Default,
+        }
+    }
+
+    fn apply(&mut self, value: Option<T>, source: Source) {
+        if let Some(value) = value {
+            self.value = value;
+            self.source = source;
+        }
+    }
+}
+
+// Valores indicados en la línea de comandos, que tienen prioridad sobre el
+// archivo y el entorno.
+#[derive(Default)]
+pub struct Overrides {
+    pub language: Option<Language>,
+    pub interval: Option<u64>,
+    pub port: Option<u16>,
+    pub bind: Option<String>,
+    pub db_path: Option<PathBuf>,
+    pub retention_days: Option<u32>,
+    pub dedup: Option<DedupRule>,
+}
+
+// Qué lecturas guarda `rusttery daemon`: todas, solo las que cambian el
+// porcentaje o las que cambian porcentaje, salud o estado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DedupRule {
+    Off,
+    Percent,
+    Changes,
+}
+
+impl fmt::Display for DedupRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DedupRule::Off => write!(f, "off"),
+            DedupRule::Percent => write!(f, "percent"),
+            DedupRule::Changes => write!(f, "changes"),
+        }
+    }
+}
+
+impl FromStr for DedupRule {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(DedupRule::Off),
+            "percent" => Ok(DedupRule::Percent),
+            "changes" => Ok(DedupRule::Changes),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    Env {
+        var: &'static str,
+        value: String,
+    },
+    Invalid {
+        key: &'static str,
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = messages();
+        match self {
+            ConfigError::Read { path, source } => write!(f, "{}", (t.read_failed)(path, source)),
+            ConfigError::Parse { path, source } => {
+                write!(f, "{}", (t.config_error)(path, source))
+            }
+            ConfigError::Env { var, value } => write!(f, "{}", (t.invalid_value)(var, value)),
+            ConfigError::Invalid { key, message } => {
+                write!(f, "{}", (t.config_invalid)(key, message))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    language: Option<Language>,
+    monitor: MonitorSection,
+    server: ServerSection,
+    database: DatabaseSection,
+    daemon: DaemonSection,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct MonitorSection {
+    interval: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ServerSection {
+    port: Option<u16>,
+    bind: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct DatabaseSection {
+    path: Option<PathBuf>,
+    retention_days: Option<u32>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct DaemonSection {
+    dedup: Option<DedupRule>,
+}
+
+impl Config {
+    // Sin ruta explícita se usa RUSTTERY_CONFIG o config.toml en el
+    // directorio de configuración del usuario (~/.config/rusttery en Linux).
+    // También fija el idioma de los mensajes.
+    pub fn load(path: Option<&Path>, overrides: Overrides) -> Result<Self, ConfigError> {
+        if let Some(language) = overrides.language {
+            i18n::set_language(language);
+        }
+
+        let path = config_path(path);
+
+        let file = match fs::read_to_string(&path) {
+            Ok(content) => Some(toml::from_str::<FileConfig>(&content).map_err(|source| {
+                ConfigError::Parse {
+                    path: path.clone(),
+                    source,
+                }
+            })?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(source) => return Err(ConfigError::Read { path, source }),
+        };
+
+        let mut config = Config {
+            path,
+            file_found: file.is_some(),
+            language: default_language(),
+            interval: Setting::new(3),
+            port: Setting::new(3000),
+            bind: Setting::new("0.0.0.0".to_string()),
+            db_path: Setting::new(Database::default_path()),
+            retention_days: Setting::new(DEFAULT_RETENTION_DAYS),
+            dedup: Setting::new(DedupRule::Off),
+        };
+
+        if let Some(file) = file {
+            config.language.apply(file.language, Source::File);
+            config.interval.apply(file.monitor.interval, Source::File);
+            config.port.apply(file.server.port, Source::File);
+            config.bind.apply(file.server.bind, Source::File);
+            config.db_path.apply(file.database.path, Source::File);
+            config
+                .retention_days
+                .apply(file.database.retention_days, Source::File);
+            config.dedup.apply(file.daemon.dedup, Source::File);
+        }
+
+        // El idioma se fija antes de validar el resto para que los errores de
+        // la propia configuración ya salgan en él.
+        apply_env(&mut config.language, "RUSTTERY_LANGUAGE")?;
+        config
+            .language
+            .apply(overrides.language, Source::Flag("--language"));
+        i18n::set_language(config.language.value);
+
+        apply_env(&mut config.interval, "RUSTTERY_INTERVAL")?;
+        apply_env(&mut config.port, "RUSTTERY_PORT")?;
+        apply_env(&mut config.bind, "RUSTTERY_BIND")?;
+        if let Some(path) = std::env::var_os("RUSTTERY_DB_PATH").filter(|p| !p.is_empty()) {
+            config
+                .db_path
+                .apply(Some(PathBuf::from(path)), Source::Env("RUSTTERY_DB_PATH"));
+        }
+        apply_env(&mut config.retention_days, "RUSTTERY_RETENTION_DAYS")?;
+        apply_env(&mut config.dedup, "RUSTTERY_DEDUP")?;
+
+        config
+            .interval
+            .apply(overrides.interval, Source::Flag("--interval"));
+        config.port.apply(overrides.port, Source::Flag("--port"));
+        config.bind.apply(overrides.bind, Source::Flag("--bind"));
+        config
+            .db_path
+            .apply(overrides.db_path, Source::Flag("--db-path"));
+        config
+            .retention_days
+            .apply(overrides.retention_days, Source::Flag("--retention-days"));
+        config.dedup.apply(overrides.dedup, Source::Flag("--dedup"));
+
+        if config.interval.value == 0 {
+            return Err(ConfigError::Invalid {
+                key: "monitor.interval",
+                message: messages().interval_too_short,
+            });
+        }
+
+        Ok(config)
+    }
+
+    // Configuración efectiva en TOML, con el origen de cada valor como
+    // comentario, de modo que la salida sirve como archivo de partida.
+    pub fn show(&self) -> String {
+        let mut lines = vec![format!(
+            "# {}",
+            (messages().config_file)(&self.path, self.file_found)
+        )];
+        lines.push(String::new());
+        lines.push(line(
+            "language = ",
+            toml_string(&self.language.value.to_string()),
+            &self.language.source,
+        ));
+        lines.push(String::new());
+        lines.push("[monitor]".to_string());
+        lines.push(line(
+            "interval = ",
+            self.interval.value,
+            &self.interval.source,
+        ));
+        lines.push(String::new());
+        lines.push("[server]".to_string());
+        lines.push(line("port = ", self.port.value, &self.port.source));
+        lines.push(line(
+            "bind = ",
+            toml_string(&self.bind.value),
+            &self.bind.source,
+        ));
+        lines.push(String::new());
+        lines.push("[database]".to_string());
+        lines.push(line(
+            "path = ",
+            toml_string(&self.db_path.value.to_string_lossy()),
+            &self.db_path.source,
+        ));
+        lines.push(line(
+            "retention_days = ",
+            self.retention_days.value,
+            &self.retention_days.source,
+        ));
+        lines.push(String::new());
+        lines.push("[daemon]".to_string());
+        lines.push(line(
+            "dedup = ",
+            toml_string(&self.dedup.value.to_string()),
+            &self.dedup.source,
+        ));
+
+        lines.join("\n")
+    }
+
+    pub fn open_database(&self) -> rusqlite::Result<Database> {
+        Database::open_with_retention(&self.db_path.value, self.retention_days.value)
+    }
+
+    // Para consultas: abre en solo lectura, sin crear la base de datos ni
+    // borrar registros, de modo que no compite con el proceso que está
+    // guardando lecturas.
+    pub fn open_database_for_reading(&self) -> rusqlite::Result<Database> {
+        Database::open_read_only(&self.db_path.value)
+    }
+}
+
+// Sin idioma en la configuración se usa el de la locale (LC_ALL,
+// LC_MESSAGES o LANG) y, si no es uno de los del catálogo, español.
+fn default_language() -> Setting<Language> {
+    match i18n::detect() {
+        Some((language, var)) => Setting {
+            value: language,
+            source: Source::Env(var),
+        },
+        None => Setting::new(Language::Es),
+    }
+}
+
+fn line(key: &str, value: impl fmt::Display, source: &Source) -> String {
+    format!("{:<40} # {}", format!("{}{}", key, value), source)
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn apply_env<T: FromStr>(setting: &mut Setting<T>, var: &'static str) -> Result<(), ConfigError> {
+    let Ok(value) = std::env::var(var) else {
+        return Ok(());
+    };
+    if value.is_empty() {
+        return Ok(());
+    }
+
+    match value.parse() {
+        Ok(parsed) => {
+            setting.apply(Some(parsed), Source::Env(var));
+            Ok(())
+        }
+        Err(_) => Err(ConfigError::Env { var, value }),
+    }
+}
+
+fn config_path(path: Option<&Path>) -> PathBuf {
+    match path {
+        Some(path) => path.to_path_buf(),
+        None => match std::env::var_os("RUSTTERY_CONFIG") {
+            Some(path) => PathBuf::from(path),
+            None => default_path(),
+        },
+    }
+}
+
+// Idioma de la ayuda y de los errores de los argumentos, que hacen falta
+// antes de que clap los analice. Sigue el orden de Config::load (--language,
+// RUSTTERY_LANGUAGE, el archivo y la locale) sin dar errores: los valores
+// mal escritos se informan después.
+pub fn language_before_parsing(args: &[String]) -> Language {
+    let option = |name: &str| {
+        args.iter().enumerate().find_map(|(i, arg)| {
+            let rest = arg.strip_prefix(name)?;
+            match rest.strip_prefix('=') {
+                Some(value) => Some(value.to_string()),
+                None if rest.is_empty() => args.get(i + 1).cloned(),
+                None => None,
+            }
+        })
+    };
+
+    let from_file = || {
+        let path = config_path(option("--config").as_deref().map(Path::new));
+        let table = fs::read_to_string(path).ok()?.parse::<toml::Table>().ok()?;
+        table.get("language")?.as_str()?.parse().ok()
+    };
+
+    option("--language")
+        .and_then(|value| value.parse().ok())
+        .or_else(|| std::env::var("RUSTTERY_LANGUAGE").ok()?.parse().ok())
+        .or_else(from_file)
+        .unwrap_or(default_language().value)
+}
+
+pub fn default_path() -> PathBuf {
+    let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    path.push("rusttery");
+    path.push("config.toml");
+    path
+}
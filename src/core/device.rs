@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+// Batería de un periférico (ratón, teclado, auriculares...). El kernel las
+// publica junto a las del sistema con `scope=Device`, y muchas solo
+// informan `capacity_level` en lugar de un porcentaje.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeviceBatteryInfo {
+    pub name: String,
+    pub scope: Option<String>,
+    pub model: Option<String>,
+    pub manufacturer: Option<String>,
+    pub serial_number: Option<String>,
+    pub percent: Option<i32>,
+    pub capacity_level: Option<String>,
+    pub status: Option<String>,
+}
+
+impl DeviceBatteryInfo {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            scope: None,
+            model: None,
+            manufacturer: None,
+            serial_number: None,
+            percent: None,
+            capacity_level: None,
+            status: None,
+        }
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.model.as_deref().unwrap_or(&self.name)
+    }
+}
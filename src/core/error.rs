@@ -0,0 +1,150 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::i18n::messages;
+
+#[derive(Debug)]
+pub enum BatteryError {
+    NoBattery,
+    UnknownBattery(String),
+    SysfsUnavailable {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        value: String,
+    },
+    Windows {
+        call: &'static str,
+        message: String,
+    },
+    ZeroCapacity {
+        name: String,
+    },
+    Unsupported,
+    ThresholdUnsupported {
+        battery: String,
+        attribute: &'static str,
+    },
+    InvalidThreshold(String),
+    Write {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Hotplug {
+        source: io::Error,
+    },
+}
+
+impl BatteryError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            BatteryError::NoBattery => "no_battery",
+            BatteryError::UnknownBattery(_) => "unknown_battery",
+            BatteryError::SysfsUnavailable { source, .. } => match source.kind() {
+                io::ErrorKind::NotFound => "sysfs_missing",
+                io::ErrorKind::PermissionDenied => "permission_denied",
+                _ => "sysfs_unreadable",
+            },
+            BatteryError::Read { source, .. } => match source.kind() {
+                io::ErrorKind::PermissionDenied => "permission_denied",
+                _ => "read_failed",
+            },
+            BatteryError::Parse { .. } => "invalid_value",
+            BatteryError::Windows { .. } => "windows_api",
+            BatteryError::ZeroCapacity { .. } => "zero_capacity",
+            BatteryError::Unsupported => "unsupported_platform",
+            BatteryError::ThresholdUnsupported { .. } => "threshold_unsupported",
+            BatteryError::InvalidThreshold(_) => "invalid_threshold",
+            BatteryError::Write { source, .. } => match source.kind() {
+                io::ErrorKind::PermissionDenied => "permission_denied",
+                _ => "write_failed",
+            },
+            BatteryError::Hotplug { .. } => "hotplug_unavailable",
+        }
+    }
+
+    pub fn http_status(&self) -> u16 {
+        match self {
+            BatteryError::NoBattery | BatteryError::UnknownBattery(_) => 404,
+            BatteryError::Unsupported | BatteryError::ThresholdUnsupported { .. } => 501,
+            BatteryError::InvalidThreshold(_) => 400,
+            _ => 500,
+        }
+    }
+
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            BatteryError::SysfsUnavailable { path, .. }
+            | BatteryError::Read { path, .. }
+            | BatteryError::Parse { path, .. }
+            | BatteryError::Write { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BatteryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let t = messages();
+        match self {
+            BatteryError::NoBattery => write!(f, "{}", t.no_battery),
+            BatteryError::UnknownBattery(name) => write!(f, "{}", (t.unknown_battery)(name)),
+            BatteryError::SysfsUnavailable { path, source } => {
+                write!(f, "{}", (t.access_failed)(path, source))
+            }
+            BatteryError::Read { path, source } => write!(f, "{}", (t.read_failed)(path, source)),
+            BatteryError::Parse { path, value } => {
+                write!(f, "{}", (t.invalid_value)(&path.display(), value))
+            }
+            BatteryError::Windows { call, message } => {
+                write!(f, "{}", (t.call_failed)(call, message))
+            }
+            BatteryError::ZeroCapacity { name } => write!(f, "{}", (t.zero_capacity)(name)),
+            BatteryError::Unsupported => write!(f, "{}", t.unsupported_platform),
+            BatteryError::ThresholdUnsupported { battery, attribute } => {
+                write!(f, "{}", (t.thresholds_unsupported)(battery, attribute))
+            }
+            BatteryError::InvalidThreshold(message) => write!(f, "{}", message),
+            BatteryError::Write { path, source } => {
+                write!(f, "{}", (t.write_failed)(path, source))
+            }
+            BatteryError::Hotplug { source } => write!(f, "{}", (t.hotplug_unavailable)(source)),
+        }
+    }
+}
+
+impl std::error::Error for BatteryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatteryError::SysfsUnavailable { source, .. }
+            | BatteryError::Read { source, .. }
+            | BatteryError::Write { source, .. }
+            | BatteryError::Hotplug { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for BatteryError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        if let Some(path) = self.path() {
+            map.serialize_entry("path", path)?;
+        }
+        if let BatteryError::Windows { call, .. } = self {
+            map.serialize_entry("call", call)?;
+        }
+        map.end()
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::BatteryInfo;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateMethod {
+    // Valor informado directamente por el kernel o el sistema operativo.
+    Kernel,
+    // energy_now / power_now
+    Power,
+    // charge_now / current_now
+    Current,
+    // Media móvil de la potencia de las últimas muestras.
+    Smoothed,
+}
+
+impl EstimateMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EstimateMethod::Kernel => "kernel",
+            EstimateMethod::Power => "power",
+            EstimateMethod::Current => "current",
+            EstimateMethod::Smoothed => "smoothed",
+        }
+    }
+}
+
+impl std::str::FromStr for EstimateMethod {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "kernel" => Ok(EstimateMethod::Kernel),
+            "power" => Ok(EstimateMethod::Power),
+            "current" => Ok(EstimateMethod::Current),
+            "smoothed" => Ok(EstimateMethod::Smoothed),
+            _ => Err(()),
+        }
+    }
+}
+
+// Rellena time_to_empty/time_to_full (en minutos) a partir de la lectura
+// instantánea cuando el origen no los proporciona.
+pub fn estimate_times(info: &mut BatteryInfo) {
+    if info.time_to_empty.is_some() || info.time_to_full.is_some() {
+        info.time_method.get_or_insert(EstimateMethod::Kernel);
+        return;
+    }
+
+    let by_power = match (info.power_now, info.energy_now_wh, info.energy_full_wh) {
+        (Some(power), Some(now), full) if power.abs() > 0.0 => {
+            Some((power.abs(), now, full, EstimateMethod::Power))
+        }
+        _ => None,
+    };
+
+    let by_current = match (info.current_now, info.charge_now_ah, info.charge_full_ah) {
+        (Some(current), Some(now), full) if current.abs() > 0.0 => {
+            Some((current.abs(), now, full, EstimateMethod::Current))
+        }
+        _ => None,
+    };
+
+    if let Some((rate, now, full, method)) = by_power.or(by_current) {
+        apply_rate(info, rate, now, full, method);
+    }
+}
+
+fn apply_rate(
+    info: &mut BatteryInfo,
+    rate: f32,
+    now: f32,
+    full: Option<f32>,
+    method: EstimateMethod,
+) {
+    match info.status.as_deref() {
+        Some("Discharging") => {
+            info.time_to_empty = Some((now / rate * 60.0).round() as i32);
+            info.time_method = Some(method);
+        }
+        Some("Charging") => {
+            if let Some(full) = full {
+                info.time_to_full = Some(((full - now).max(0.0) / rate * 60.0).round() as i32);
+                info.time_method = Some(method);
+            }
+        }
+        _ => {}
+    }
+}
+
+const MIN_SAMPLE_SPACING: Duration = Duration::from_secs(1);
+
+struct Sample {
+    at: Instant,
+    energy_wh: f32,
+    power_w: Option<f32>,
+}
+
+// Suaviza las estimaciones con las muestras de cada batería tomadas dentro
+// de una ventana de tiempo, de modo que una ráfaga de lecturas no la acorte.
+// La ventana se reinicia cuando cambia el estado de carga.
+pub struct TimeEstimator {
+    window: Duration,
+    samples: HashMap<String, (Option<String>, VecDeque<Sample>)>,
+}
+
+impl TimeEstimator {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window: window.max(MIN_SAMPLE_SPACING),
+            samples: HashMap::new(),
+        }
+    }
+
+    pub fn apply(&mut self, info: &mut BatteryInfo) {
+        self.apply_at(info, Instant::now());
+    }
+
+    fn apply_at(&mut self, info: &mut BatteryInfo, sample_at: Instant) {
+        estimate_times(info);
+
+        if info.time_method == Some(EstimateMethod::Kernel) {
+            return;
+        }
+
+        let Some(energy_wh) = info.energy_now_wh else {
+            return;
+        };
+
+        let (status, samples) = self
+            .samples
+            .entry(info.name.clone())
+            .or_insert_with(|| (info.status.clone(), VecDeque::new()));
+
+        if *status != info.status {
+            *status = info.status.clone();
+            samples.clear();
+        }
+
+        let sample = Sample {
+            at: sample_at,
+            energy_wh,
+            power_w: info.power_now.map(f32::abs).filter(|p| *p > 0.0),
+        };
+
+        // Lecturas muy seguidas (p. ej. repintados de la GUI) sustituyen a
+        // la última muestra en lugar de llenar la ventana.
+        match samples.back_mut() {
+            Some(last) if sample.at.duration_since(last.at) < MIN_SAMPLE_SPACING => *last = sample,
+            _ => samples.push_back(sample),
+        }
+        while samples
+            .front()
+            .is_some_and(|first| sample_at.duration_since(first.at) > self.window)
+        {
+            samples.pop_front();
+        }
+
+        if let Some(rate) = average_power(samples) {
+            let full = info.energy_full_wh;
+            info.time_to_empty = None;
+            info.time_to_full = None;
+            apply_rate(info, rate, energy_wh, full, EstimateMethod::Smoothed);
+        }
+    }
+}
+
+fn average_power(samples: &VecDeque<Sample>) -> Option<f32> {
+    if samples.len() < 2 {
+        return None;
+    }
+
+    let readings: Vec<f32> = samples.iter().filter_map(|s| s.power_w).collect();
+    if readings.len() == samples.len() {
+        return Some(readings.iter().sum::<f32>() / readings.len() as f32);
+    }
+
+    // Sin lecturas de potencia, se usa la pendiente de la energía.
+    let first = samples.front()?;
+    let last = samples.back()?;
+    let hours = last.at.duration_since(first.at).as_secs_f32() / 3600.0;
+    let delta = (last.energy_wh - first.energy_wh).abs();
+    if hours > 0.0 && delta > 0.0 {
+        Some(delta / hours)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(status: &str) -> BatteryInfo {
+        let mut info = BatteryInfo::new("BAT0", 50);
+        info.status = Some(status.to_string());
+        info
+    }
+
+    #[test]
+    fn estimates_from_power() {
+        let mut info = battery("Discharging");
+        info.energy_now_wh = Some(30.0);
+        info.energy_full_wh = Some(60.0);
+        info.power_now = Some(-15.0);
+        estimate_times(&mut info);
+        assert_eq!(info.time_to_empty, Some(120));
+        assert_eq!(info.time_to_full, None);
+        assert_eq!(info.time_method, Some(EstimateMethod::Power));
+
+        let mut info = battery("Charging");
+        info.energy_now_wh = Some(30.0);
+        info.energy_full_wh = Some(60.0);
+        info.power_now = Some(20.0);
+        estimate_times(&mut info);
+        assert_eq!(info.time_to_full, Some(90));
+    }
+
+    #[test]
+    fn falls_back_to_current() {
+        let mut info = battery("Discharging");
+        info.energy_now_wh = Some(30.0);
+        info.power_now = Some(0.0);
+        info.charge_now_ah = Some(2.0);
+        info.current_now = Some(4.0);
+        estimate_times(&mut info);
+        assert_eq!(info.time_to_empty, Some(30));
+        assert_eq!(info.time_method, Some(EstimateMethod::Current));
+    }
+
+    #[test]
+    fn keeps_kernel_times() {
+        let mut info = battery("Discharging");
+        info.time_to_empty = Some(42);
+        info.energy_now_wh = Some(30.0);
+        info.power_now = Some(15.0);
+        estimate_times(&mut info);
+        assert_eq!(info.time_to_empty, Some(42));
+        assert_eq!(info.time_method, Some(EstimateMethod::Kernel));
+    }
+
+    #[test]
+    fn smoothing_window_is_time_based() {
+        let mut estimator = TimeEstimator::new(Duration::from_secs(60));
+        let start = Instant::now();
+        let mut reading = |seconds: u64, power: f32| {
+            let mut info = battery("Discharging");
+            info.energy_now_wh = Some(30.0);
+            info.power_now = Some(power);
+            estimator.apply_at(&mut info, start + Duration::from_secs(seconds));
+            info
+        };
+
+        assert_eq!(reading(0, 10.0).time_method, Some(EstimateMethod::Power));
+        assert_eq!(reading(30, 10.0).time_to_empty, Some(180));
+
+        // Una ráfaga de lecturas no expulsa de la ventana las primeras
+        // muestras.
+        for second in 31..40 {
+            reading(second, 40.0);
+        }
+        let info = reading(40, 40.0);
+        assert_eq!(info.time_method, Some(EstimateMethod::Smoothed));
+        assert_eq!(info.time_to_empty, Some(51));
+
+        // Pasada la ventana, solo cuentan las muestras recientes.
+        assert_eq!(reading(100, 40.0).time_to_empty, Some(45));
+        let info = reading(200, 20.0);
+        assert_eq!(info.time_method, Some(EstimateMethod::Power));
+        assert_eq!(info.time_to_empty, Some(90));
+    }
+
+    #[test]
+    fn no_estimate_when_idle() {
+        let mut info = battery("Full");
+        info.energy_now_wh = Some(60.0);
+        info.energy_full_wh = Some(60.0);
+        info.power_now = Some(1.0);
+        estimate_times(&mut info);
+        assert_eq!(info.time_to_empty, None);
+        assert_eq!(info.time_to_full, None);
+        assert_eq!(info.time_method, None);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryIter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+// Cambios de hardware notificados por el sistema en el momento en que
+// ocurren, sin esperar al siguiente sondeo.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum PowerEvent {
+    ChargerConnected {
+        name: String,
+    },
+    ChargerDisconnected {
+        name: String,
+    },
+    BatteryAdded {
+        name: String,
+    },
+    BatteryRemoved {
+        name: String,
+    },
+    StatusChanged {
+        name: String,
+        status: Option<String>,
+    },
+}
+
+impl PowerEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PowerEvent::ChargerConnected { .. } => "charger_connected",
+            PowerEvent::ChargerDisconnected { .. } => "charger_disconnected",
+            PowerEvent::BatteryAdded { .. } => "battery_added",
+            PowerEvent::BatteryRemoved { .. } => "battery_removed",
+            PowerEvent::StatusChanged { .. } => "status_changed",
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PowerEvent::ChargerConnected { name }
+            | PowerEvent::ChargerDisconnected { name }
+            | PowerEvent::BatteryAdded { name }
+            | PowerEvent::BatteryRemoved { name }
+            | PowerEvent::StatusChanged { name, .. } => name,
+        }
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        match self {
+            PowerEvent::StatusChanged { status, .. } => status.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+// Canal por el que llegan los eventos. Al soltarlo se avisa al hilo que los
+// produce para que termine y libere sus recursos, aunque no lleguen más
+// eventos que enviar.
+pub struct PowerEvents {
+    receiver: Receiver<PowerEvent>,
+    stop: Arc<AtomicBool>,
+}
+
+impl PowerEvents {
+    pub fn new(receiver: Receiver<PowerEvent>, stop: Arc<AtomicBool>) -> Self {
+        Self { receiver, stop }
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<PowerEvent, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    pub fn try_iter(&self) -> TryIter<'_, PowerEvent> {
+        self.receiver.try_iter()
+    }
+}
+
+impl Drop for PowerEvents {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
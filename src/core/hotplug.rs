@@ -0,0 +1,431 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::mem;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use super::uevent::parse_uevent;
+use super::{PowerEvent, PowerEvents, PowerSourceKind};
+
+// Grupo multicast por el que el kernel publica los uevent (el grupo 2 es el
+// que reenvía udevd, con otro formato).
+const KERNEL_GROUP: u32 = 1;
+
+// Cada cuánto deja de esperar mensajes el hilo para comprobar si se ha
+// soltado el canal de eventos.
+const STOP_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+// Abre el socket netlink de uevent y traduce los mensajes del subsistema
+// power_supply en eventos, que se entregan por el canal devuelto. El estado
+// inicial se toma de los archivos uevent de `root`. El hilo termina, y cierra
+// el socket, poco después de soltar el canal.
+pub fn listen(root: &Path) -> io::Result<PowerEvents> {
+    let socket = UeventSocket::open()?;
+    let mut tracker = EventTracker::default();
+    tracker.seed(root);
+
+    let (sender, receiver) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    thread::Builder::new()
+        .name("rusttery-hotplug".to_string())
+        .spawn(move || {
+            let mut buffer = vec![0u8; 16 * 1024];
+            while !stopped.load(Ordering::Relaxed) {
+                let message = match socket.recv(&mut buffer) {
+                    Ok(Some(message)) => message,
+                    Ok(None) => continue,
+                    // Interrupciones y desbordamientos del búfer del socket
+                    // solo suponen perder algún mensaje.
+                    Err(e) if matches!(e.raw_os_error(), Some(libc::EINTR | libc::ENOBUFS)) => {
+                        continue
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => continue,
+                    Err(_) => return,
+                };
+
+                let Some((action, name, properties)) = parse_message(message) else {
+                    continue;
+                };
+
+                for event in tracker.handle(&action, &name, &properties) {
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        })?;
+
+    Ok(PowerEvents::new(receiver, stop))
+}
+
+struct UeventSocket(OwnedFd);
+
+impl UeventSocket {
+    fn open() -> io::Result<Self> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+                libc::NETLINK_KOBJECT_UEVENT,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = Self(unsafe { OwnedFd::from_raw_fd(fd) });
+
+        let timeout = libc::timeval {
+            tv_sec: STOP_CHECK_INTERVAL.as_secs() as libc::time_t,
+            tv_usec: 0,
+        };
+        let result = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_RCVTIMEO,
+                &timeout as *const libc::timeval as *const libc::c_void,
+                mem::size_of::<libc::timeval>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        address.nl_groups = KERNEL_GROUP;
+        let result = unsafe {
+            libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(socket)
+    }
+
+    // Solo se aceptan mensajes enviados por el kernel (nl_pid 0).
+    fn recv<'a>(&self, buffer: &'a mut [u8]) -> io::Result<Option<&'a [u8]>> {
+        let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        let mut length = mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+        let received = unsafe {
+            libc::recvfrom(
+                self.0.as_raw_fd(),
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+                0,
+                &mut address as *mut libc::sockaddr_nl as *mut libc::sockaddr,
+                &mut length,
+            )
+        };
+        if received < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if address.nl_pid != 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(&buffer[..received as usize]))
+    }
+}
+
+// Un mensaje es "change@/devices/...\0ACTION=change\0SUBSYSTEM=power_supply\0
+// POWER_SUPPLY_NAME=BAT0\0...". Devuelve la acción, el nombre de la entrada
+// y sus atributos.
+fn parse_message(message: &[u8]) -> Option<(String, String, HashMap<String, String>)> {
+    let text = String::from_utf8_lossy(message);
+
+    let mut action = None;
+    let mut subsystem = None;
+    let mut devpath = None;
+    for field in text.split('\0') {
+        match field.split_once('=') {
+            Some(("ACTION", value)) => action = Some(value),
+            Some(("SUBSYSTEM", value)) => subsystem = Some(value),
+            Some(("DEVPATH", value)) => devpath = Some(value),
+            _ => {}
+        }
+    }
+
+    if subsystem != Some("power_supply") {
+        return None;
+    }
+
+    let properties = parse_uevent(&text);
+    let name = properties
+        .get("name")
+        .map(String::as_str)
+        .or_else(|| devpath?.rsplit('/').next())?
+        .to_string();
+
+    Some((action?.to_string(), name, properties))
+}
+
+#[derive(Default)]
+struct EntryState {
+    battery: bool,
+    charger: bool,
+    present: Option<bool>,
+    online: Option<bool>,
+    status: Option<String>,
+}
+
+impl EntryState {
+    fn update(&mut self, properties: &HashMap<String, String>) {
+        if let Some(kind) = properties.get("type") {
+            self.battery = kind == "Battery";
+            self.charger = PowerSourceKind::from_sysfs_type(kind).is_some();
+        }
+        if let Some(present) = properties.get("present") {
+            self.present = Some(present == "1");
+        }
+        if let Some(online) = properties.get("online") {
+            self.online = Some(online == "1");
+        }
+        if properties.contains_key("status") {
+            self.status = properties.get("status").cloned();
+        }
+    }
+}
+
+// Los uevent "change" repiten todos los atributos de la entrada, así que
+// hay que compararlos con el último estado conocido para saber qué cambió.
+#[derive(Default)]
+struct EventTracker {
+    entries: HashMap<String, EntryState>,
+}
+
+impl EventTracker {
+    fn seed(&mut self, root: &Path) {
+        let Ok(entries) = fs::read_dir(root) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let Ok(content) = fs::read_to_string(entry.path().join("uevent")) else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            self.entries
+                .entry(name)
+                .or_default()
+                .update(&parse_uevent(&content));
+        }
+    }
+
+    fn handle(
+        &mut self,
+        action: &str,
+        name: &str,
+        properties: &HashMap<String, String>,
+    ) -> Vec<PowerEvent> {
+        let mut events = Vec::new();
+        let name = name.to_string();
+
+        if action == "remove" {
+            if let Some(previous) = self.entries.remove(&name) {
+                if previous.battery && previous.present != Some(false) {
+                    events.push(PowerEvent::BatteryRemoved { name });
+                } else if previous.charger && previous.online == Some(true) {
+                    events.push(PowerEvent::ChargerDisconnected { name });
+                }
+            }
+            return events;
+        }
+
+        let known = self.entries.contains_key(&name);
+        let state = self.entries.entry(name.clone()).or_default();
+        let previous_present = state.present;
+        let previous_online = state.online;
+        let previous_status = state.status.clone();
+        state.update(properties);
+
+        if state.battery {
+            // Las bahías extraíbles mantienen la entrada y cambian `present`.
+            let present = state.present != Some(false);
+            let was_present = if known {
+                previous_present != Some(false)
+            } else {
+                action != "add"
+            };
+
+            if present && !was_present {
+                events.push(PowerEvent::BatteryAdded { name: name.clone() });
+            } else if !present && was_present {
+                events.push(PowerEvent::BatteryRemoved { name: name.clone() });
+            }
+
+            if present && was_present && known && state.status != previous_status {
+                events.push(PowerEvent::StatusChanged {
+                    name,
+                    status: state.status.clone(),
+                });
+            }
+        } else if state.charger {
+            let online = state.online == Some(true);
+            // Sin estado previo, un "change" de un cargador casi siempre
+            // significa que se ha conectado o desconectado.
+            let changed = match previous_online {
+                Some(previous) => previous != online,
+                None => action == "change" || online,
+            };
+
+            if changed {
+                events.push(if online {
+                    PowerEvent::ChargerConnected { name }
+                } else {
+                    PowerEvent::ChargerDisconnected { name }
+                });
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(action: &str, subsystem: &str, properties: &[&str]) -> Vec<u8> {
+        let mut fields = vec![
+            format!("{}@/devices/platform/power_supply/X", action),
+            format!("ACTION={}", action),
+            format!("SUBSYSTEM={}", subsystem),
+        ];
+        fields.extend(properties.iter().map(|p| p.to_string()));
+        fields.join("\0").into_bytes()
+    }
+
+    fn feed(tracker: &mut EventTracker, message: &[u8]) -> Vec<PowerEvent> {
+        match parse_message(message) {
+            Some((action, name, properties)) => tracker.handle(&action, &name, &properties),
+            None => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn charger_online_flips() {
+        let root = tempfile::tempdir().unwrap();
+        let ac = root.path().join("AC");
+        fs::create_dir(&ac).unwrap();
+        fs::write(
+            ac.join("uevent"),
+            "POWER_SUPPLY_NAME=AC\nPOWER_SUPPLY_TYPE=Mains\nPOWER_SUPPLY_ONLINE=0\n",
+        )
+        .unwrap();
+        let mut tracker = EventTracker::default();
+        tracker.seed(root.path());
+
+        let online = |value: &str| {
+            message(
+                "change",
+                "power_supply",
+                &[
+                    "POWER_SUPPLY_NAME=AC",
+                    "POWER_SUPPLY_TYPE=Mains",
+                    &format!("POWER_SUPPLY_ONLINE={}", value),
+                ],
+            )
+        };
+
+        assert_eq!(
+            feed(&mut tracker, &online("1")),
+            [PowerEvent::ChargerConnected {
+                name: "AC".to_string()
+            }]
+        );
+        assert!(feed(&mut tracker, &online("1")).is_empty());
+        assert_eq!(
+            feed(&mut tracker, &online("0")),
+            [PowerEvent::ChargerDisconnected {
+                name: "AC".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn battery_add_and_remove() {
+        let mut tracker = EventTracker::default();
+        let add = message(
+            "add",
+            "power_supply",
+            &[
+                "POWER_SUPPLY_NAME=BAT1",
+                "POWER_SUPPLY_TYPE=Battery",
+                "POWER_SUPPLY_STATUS=Discharging",
+            ],
+        );
+        let added = [PowerEvent::BatteryAdded {
+            name: "BAT1".to_string(),
+        }];
+
+        assert_eq!(feed(&mut tracker, &add), added);
+        // Un "add" repetido de una batería ya conocida no es otra inserción.
+        assert!(feed(&mut tracker, &add).is_empty());
+
+        let change = message(
+            "change",
+            "power_supply",
+            &[
+                "POWER_SUPPLY_NAME=BAT1",
+                "POWER_SUPPLY_TYPE=Battery",
+                "POWER_SUPPLY_STATUS=Charging",
+            ],
+        );
+        assert_eq!(
+            feed(&mut tracker, &change),
+            [PowerEvent::StatusChanged {
+                name: "BAT1".to_string(),
+                status: Some("Charging".to_string()),
+            }]
+        );
+
+        let remove = message("remove", "power_supply", &["POWER_SUPPLY_NAME=BAT1"]);
+        assert_eq!(
+            feed(&mut tracker, &remove),
+            [PowerEvent::BatteryRemoved {
+                name: "BAT1".to_string()
+            }]
+        );
+        assert!(feed(&mut tracker, &remove).is_empty());
+        assert_eq!(feed(&mut tracker, &add), added);
+    }
+
+    #[test]
+    fn ignores_other_subsystems() {
+        let usb = message(
+            "add",
+            "usb",
+            &["POWER_SUPPLY_NAME=BAT0", "PRODUCT=46d/c52b/1211"],
+        );
+        assert!(parse_message(&usb).is_none());
+
+        let mut tracker = EventTracker::default();
+        assert!(feed(&mut tracker, &usb).is_empty());
+        assert!(tracker.entries.is_empty());
+    }
+
+    #[test]
+    fn name_falls_back_to_devpath() {
+        let message = message(
+            "change",
+            "power_supply",
+            &["DEVPATH=/devices/LNXSYSTM:00/ACPI0003:00/power_supply/ADP1"],
+        );
+        let (action, name, _) = parse_message(&message).unwrap();
+        assert_eq!(action, "change");
+        assert_eq!(name, "ADP1");
+    }
+}
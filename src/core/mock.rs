@@ -0,0 +1,85 @@
+use super::{BatteryError, BatteryInfo, BatterySource};
+
+// Fuente en memoria para ejecutar Rusttery en equipos sin batería.
+pub struct MockSource {
+    batteries: Vec<BatteryInfo>,
+}
+
+impl MockSource {
+    pub fn new(batteries: Vec<BatteryInfo>) -> Self {
+        Self { batteries }
+    }
+
+    // Formato: "BAT0=85,BAT1=60:Charging". Una entrada mal escrita es un
+    // error, no una batería que desaparece sin avisar.
+    pub fn from_spec(spec: &str) -> Result<Self, BatteryError> {
+        let batteries = spec
+            .split(',')
+            .map(|entry| {
+                parse_entry(entry.trim()).ok_or_else(|| BatteryError::Parse {
+                    path: "RUSTTERY_MOCK_BATTERIES".into(),
+                    value: entry.trim().to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(batteries))
+    }
+}
+
+fn parse_entry(entry: &str) -> Option<BatteryInfo> {
+    let (name, rest) = entry.split_once('=')?;
+    let (percent, status) = match rest.split_once(':') {
+        Some((percent, status)) => (percent, Some(status.to_string())),
+        None => (rest, None),
+    };
+    let percent: i32 = percent.trim().parse().ok()?;
+    if name.trim().is_empty() || !(0..=100).contains(&percent) {
+        return None;
+    }
+    let mut info = BatteryInfo::new(name.trim(), percent);
+    info.status = status;
+    Some(info)
+}
+
+impl BatterySource for MockSource {
+    fn batteries(&self) -> Result<Vec<BatteryInfo>, BatteryError> {
+        Ok(self.batteries.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_spec() {
+        let source = MockSource::from_spec("BAT0=85, BAT1=60:Charging").unwrap();
+        let batteries = source.batteries().unwrap();
+
+        assert_eq!(batteries.len(), 2);
+        assert_eq!(batteries[0].name, "BAT0");
+        assert_eq!(batteries[0].percent, 85);
+        assert_eq!(batteries[0].status, None);
+        assert_eq!(batteries[1].name, "BAT1");
+        assert_eq!(batteries[1].percent, 60);
+        assert_eq!(batteries[1].status.as_deref(), Some("Charging"));
+    }
+
+    #[test]
+    fn rejects_bad_entries() {
+        for spec in [
+            "BAT0",
+            "BAT0=lleno",
+            "=50",
+            "BAT0=85,",
+            "BAT0=101",
+            "BAT0=-1",
+        ] {
+            match MockSource::from_spec(spec) {
+                Err(BatteryError::Parse { .. }) => {}
+                _ => panic!("'{}' debería ser un error", spec),
+            }
+        }
+    }
+}
@@ -1,14 +1,69 @@
 use serde::{Deserialize, Serialize};
 
+mod device;
+mod error;
+mod estimate;
+mod event;
+#[cfg(target_os = "linux")]
+mod hotplug;
+mod mock;
+mod monitor;
+mod power_source;
+mod sysfs;
+mod threshold;
+mod uevent;
 #[cfg(windows)]
-use windows::Devices::Power::Battery;
+mod windows;
 
-#[cfg(target_os = "linux")]
-use std::fs;
+#[cfg(windows)]
+pub use self::windows::WindowsSource;
+pub use device::DeviceBatteryInfo;
+pub use error::BatteryError;
+pub use estimate::{estimate_times, EstimateMethod, TimeEstimator};
+pub use event::{PowerEvent, PowerEvents};
+pub use mock::MockSource;
+pub use monitor::{BatteryChange, BatteryMonitor, ChangeFilter, Snapshot};
+pub use power_source::{on_external_power, PowerSourceInfo, PowerSourceKind};
+pub use sysfs::SysfsSource;
+pub use threshold::{ChargeThresholds, ThresholdUpdate};
+pub use uevent::ReadMethod;
+
+// Magnitud en la que el hardware informa la capacidad: energía (Wh) o
+// carga (Ah). Los campos `energy_*_wh` se rellenan siempre que sea posible
+// para que los valores sean comparables entre equipos.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityUnit {
+    Energy,
+    Charge,
+}
+
+impl CapacityUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapacityUnit::Energy => "energy",
+            CapacityUnit::Charge => "charge",
+        }
+    }
+}
+
+impl std::str::FromStr for CapacityUnit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "energy" => Ok(CapacityUnit::Energy),
+            "charge" => Ok(CapacityUnit::Charge),
+            _ => Err(()),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BatteryInfo {
+    pub name: String,
     pub percent: i32,
+    pub percent_exact: Option<f32>,
     pub health: Option<i32>,
     pub status: Option<String>,
     pub cycle_count: Option<i32>,
@@ -19,230 +74,352 @@ pub struct BatteryInfo {
     pub manufacturer: Option<String>,
     pub model: Option<String>,
     pub serial_number: Option<String>,
-    pub capacity_full: Option<i32>,
-    pub capacity_design: Option<i32>,
+    pub capacity_unit: Option<CapacityUnit>,
+    pub energy_now_wh: Option<f32>,
+    pub energy_full_wh: Option<f32>,
+    pub energy_full_design_wh: Option<f32>,
+    pub charge_now_ah: Option<f32>,
+    pub charge_full_ah: Option<f32>,
+    pub charge_full_design_ah: Option<f32>,
+    pub voltage_min_design: Option<f32>,
     pub time_to_empty: Option<i32>,
     pub time_to_full: Option<i32>,
+    pub time_method: Option<EstimateMethod>,
+    pub temperature: Option<f32>,
+    pub capacity_level: Option<String>,
+    pub alarm: Option<f32>,
+    pub present: Option<bool>,
+    pub health_status: Option<String>,
+    pub read_method: Option<ReadMethod>,
+}
+
+impl BatteryInfo {
+    pub fn new(name: impl Into<String>, percent: i32) -> Self {
+        Self {
+            name: name.into(),
+            percent,
+            percent_exact: None,
+            health: None,
+            status: None,
+            cycle_count: None,
+            voltage_now: None,
+            current_now: None,
+            power_now: None,
+            technology: None,
+            manufacturer: None,
+            model: None,
+            serial_number: None,
+            capacity_unit: None,
+            energy_now_wh: None,
+            energy_full_wh: None,
+            energy_full_design_wh: None,
+            charge_now_ah: None,
+            charge_full_ah: None,
+            charge_full_design_ah: None,
+            voltage_min_design: None,
+            time_to_empty: None,
+            time_to_full: None,
+            time_method: None,
+            temperature: None,
+            capacity_level: None,
+            alarm: None,
+            present: None,
+            health_status: None,
+            read_method: None,
+        }
+    }
+}
+
+pub trait BatterySource: Send + Sync {
+    fn batteries(&self) -> Result<Vec<BatteryInfo>, BatteryError>;
+
+    fn power_sources(&self) -> Result<Vec<PowerSourceInfo>, BatteryError> {
+        Ok(Vec::new())
+    }
+
+    fn device_batteries(&self) -> Result<Vec<DeviceBatteryInfo>, BatteryError> {
+        Ok(Vec::new())
+    }
+
+    fn charge_thresholds(&self, battery: &str) -> Result<ChargeThresholds, BatteryError> {
+        Err(BatteryError::ThresholdUnsupported {
+            battery: battery.to_string(),
+            attribute: "charge_control_end_threshold",
+        })
+    }
+
+    fn set_charge_thresholds(
+        &self,
+        battery: &str,
+        _update: &ThresholdUpdate,
+    ) -> Result<ChargeThresholds, BatteryError> {
+        Err(BatteryError::ThresholdUnsupported {
+            battery: battery.to_string(),
+            attribute: "charge_control_end_threshold",
+        })
+    }
+
+    // Eventos de conexión y desconexión en tiempo real, para no depender
+    // solo del sondeo periódico.
+    fn power_events(&self) -> Result<PowerEvents, BatteryError> {
+        Err(BatteryError::Hotplug {
+            source: std::io::ErrorKind::Unsupported.into(),
+        })
+    }
+}
+
+// Vista combinada de todas las baterías del sistema.
+pub fn get_battery_info() -> Result<BatteryInfo, BatteryError> {
+    combine_batteries(&get_all_batteries()?).ok_or(BatteryError::NoBattery)
+}
+
+pub fn get_all_batteries() -> Result<Vec<BatteryInfo>, BatteryError> {
+    default_source()?.batteries()
+}
+
+// RUSTTERY_MOCK_BATTERIES y RUSTTERY_SYSFS_ROOT permiten ejecutar todo el
+// programa contra datos simulados o un árbol sysfs de prueba.
+pub fn default_source() -> Result<Box<dyn BatterySource>, BatteryError> {
+    if let Ok(spec) = std::env::var("RUSTTERY_MOCK_BATTERIES") {
+        return Ok(Box::new(MockSource::from_spec(&spec)?));
+    }
+
+    if let Ok(root) = std::env::var("RUSTTERY_SYSFS_ROOT") {
+        return Ok(Box::new(SysfsSource::new(root)));
+    }
+
+    Ok(platform_source())
 }
 
 #[cfg(windows)]
-pub fn get_battery_info() -> Option<BatteryInfo> {
-    let battery = Battery::AggregateBattery().ok()?;
-    let report = battery.GetReport().ok()?;
-
-    let full_ref = report.FullChargeCapacityInMilliwattHours().ok()?;
-    let full = full_ref.Value().ok()? as f32;
-
-    let remaining_ref = report.RemainingCapacityInMilliwattHours().ok()?;
-    let remaining = remaining_ref.Value().ok()? as f32;
-
-    if full <= 0.0 {
-        return None;
-    }
-
-    let percent = ((remaining / full) * 100.0).round() as i32;
-    
-    let (health, capacity_design) = if let Ok(design_ref) = report.DesignCapacityInMilliwattHours() {
-        if let Ok(design) = design_ref.Value() {
-            let design = design as f32;
-            if design > 0.0 {
-                (Some(((full / design) * 100.0).round() as i32), Some((design / 1000.0) as i32))
-            } else {
-                (None, None)
-            }
-        } else {
-            (None, None)
+fn platform_source() -> Box<dyn BatterySource> {
+    Box::new(WindowsSource::new())
+}
+
+#[cfg(target_os = "linux")]
+fn platform_source() -> Box<dyn BatterySource> {
+    Box::new(SysfsSource::default())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+struct UnsupportedSource;
+
+#[cfg(not(any(windows, target_os = "linux")))]
+impl BatterySource for UnsupportedSource {
+    fn batteries(&self) -> Result<Vec<BatteryInfo>, BatteryError> {
+        Err(BatteryError::Unsupported)
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn platform_source() -> Box<dyn BatterySource> {
+    Box::new(UnsupportedSource)
+}
+
+// Vista combinada de varias baterías: energía sumada y porcentaje ponderado
+// por la capacidad de cada una. Con una sola batería se devuelve tal cual.
+pub fn combine_batteries(batteries: &[BatteryInfo]) -> Option<BatteryInfo> {
+    match batteries {
+        [] => return None,
+        [single] => return Some(single.clone()),
+        _ => {}
+    }
+
+    let name = batteries
+        .iter()
+        .map(|b| b.name.as_str())
+        .collect::<Vec<_>>()
+        .join("+");
+
+    let energy_now_wh = sum_all(batteries.iter().map(|b| b.energy_now_wh));
+    let energy_full_wh = sum_all(batteries.iter().map(|b| b.energy_full_wh));
+    let energy_full_design_wh = sum_all(batteries.iter().map(|b| b.energy_full_design_wh));
+
+    let percent = match energy_full_wh {
+        Some(total) if total > 0.0 => {
+            let weighted: f32 = batteries
+                .iter()
+                .map(|b| b.percent as f32 * b.energy_full_wh.unwrap_or(0.0))
+                .sum();
+            (weighted / total).round() as i32
+        }
+        _ => {
+            let sum: i32 = batteries.iter().map(|b| b.percent).sum();
+            (sum as f32 / batteries.len() as f32).round() as i32
         }
-    } else {
-        (None, None)
     };
 
-    let status = if let Ok(status_ref) = report.Status() {
-        match status_ref.0 {
-            1 => Some("Discharging".to_string()),
-            2 => Some("Idle".to_string()),
-            3 => Some("Charging".to_string()),
-            _ => Some("Unknown".to_string()),
-        }
-    } else {
-        None
+    let percent_exact = match (energy_now_wh, energy_full_wh) {
+        (Some(now), Some(full)) => exact_percent(now, full),
+        _ => None,
     };
 
-    let charge_rate = report.ChargeRateInMilliwatts()
-        .ok()
-        .and_then(|r| r.Value().ok())
-        .map(|v| v as f32 / 1000.0);
-
-    let (time_to_empty, time_to_full) = if let Some(rate) = charge_rate {
-        if rate > 0.0 {
-            let time_full = ((full - remaining) / rate * 60.0) as i32;
-            (None, Some(time_full))
-        } else if rate < 0.0 {
-            let time_empty = (remaining / rate.abs() * 60.0) as i32;
-            (Some(time_empty), None)
-        } else {
-            (None, None)
-        }
-    } else {
-        (None, None)
+    let health = match (energy_full_wh, energy_full_design_wh) {
+        (Some(full), Some(design)) => health_percent(full, design),
+        _ => None,
     };
 
-    Some(BatteryInfo {
+    let capacity_unit = batteries[0].capacity_unit;
+    let same_unit = batteries.iter().all(|b| b.capacity_unit == capacity_unit);
+
+    let status = ["Charging", "Discharging", "Full"]
+        .iter()
+        .find(|s| batteries.iter().any(|b| b.status.as_deref() == Some(**s)))
+        .map(|s| s.to_string())
+        .or_else(|| batteries.iter().find_map(|b| b.status.clone()));
+
+    let mut combined = BatteryInfo {
+        name,
         percent: percent.clamp(0, 100),
-        health: health.map(|h| h.clamp(0, 100)),
+        percent_exact,
+        health,
         status,
-        cycle_count: None,
+        cycle_count: batteries.iter().filter_map(|b| b.cycle_count).max(),
         voltage_now: None,
-        current_now: None,
-        power_now: charge_rate,
-        technology: None,
-        manufacturer: None,
-        model: None,
+        current_now: sum_any(batteries.iter().map(|b| b.current_now)),
+        power_now: sum_any(batteries.iter().map(|b| b.power_now)),
+        technology: shared_value(batteries.iter().map(|b| &b.technology)),
+        manufacturer: shared_value(batteries.iter().map(|b| &b.manufacturer)),
+        model: shared_value(batteries.iter().map(|b| &b.model)),
         serial_number: None,
-        capacity_full: Some((full / 1000.0) as i32),
-        capacity_design,
-        time_to_empty,
-        time_to_full,
-    })
-}
+        capacity_unit: if same_unit { capacity_unit } else { None },
+        energy_now_wh,
+        energy_full_wh,
+        energy_full_design_wh,
+        charge_now_ah: sum_all(batteries.iter().map(|b| b.charge_now_ah)),
+        charge_full_ah: sum_all(batteries.iter().map(|b| b.charge_full_ah)),
+        charge_full_design_ah: sum_all(batteries.iter().map(|b| b.charge_full_design_ah)),
+        voltage_min_design: None,
+        time_to_empty: None,
+        time_to_full: None,
+        time_method: None,
+        temperature: batteries
+            .iter()
+            .filter_map(|b| b.temperature)
+            .max_by(|a, b| a.total_cmp(b)),
+        capacity_level: shared_value(batteries.iter().map(|b| &b.capacity_level)),
+        alarm: None,
+        present: Some(true),
+        health_status: shared_value(batteries.iter().map(|b| &b.health_status)),
+        read_method: batteries[0]
+            .read_method
+            .filter(|m| batteries.iter().all(|b| b.read_method == Some(*m))),
+    };
 
-#[cfg(target_os = "linux")]
-pub fn get_battery_info() -> Option<BatteryInfo> {
-    let power_supply_path = "/sys/class/power_supply";
-    
-    let entries = fs::read_dir(power_supply_path).ok()?;
-    
-    for entry in entries.flatten() {
-        let path = entry.path();
-    
-        let type_path = path.join("type");
-        if let Ok(device_type) = fs::read_to_string(&type_path) {
-            if device_type.trim() != "Battery" {
-                continue;
-            }
-        } else {
-            continue;
+    // Con energía y potencia totales la estimación es directa. Si no, las
+    // baterías internas se descargan una detrás de otra y los tiempos de
+    // cada una se acumulan.
+    estimate_times(&mut combined);
+    if combined.time_method.is_none() {
+        combined.time_to_empty = sum_any(batteries.iter().map(|b| b.time_to_empty));
+        combined.time_to_full = sum_any(batteries.iter().map(|b| b.time_to_full));
+        if combined.time_to_empty.is_some() || combined.time_to_full.is_some() {
+            combined.time_method = batteries.iter().find_map(|b| b.time_method);
         }
-        
-        let capacity_path = path.join("capacity");
-        let percent = if let Ok(capacity_str) = fs::read_to_string(&capacity_path) {
-            if let Ok(capacity) = capacity_str.trim().parse::<i32>() {
-                capacity.clamp(0, 100)
-            } else {
-                continue;
-            }
-        } else {
-            continue;
-        };
-        
-        let status = fs::read_to_string(path.join("status"))
-            .ok()
-            .map(|s| s.trim().to_string());
-
-        let cycle_count = fs::read_to_string(path.join("cycle_count"))
-            .ok()
-            .and_then(|s| s.trim().parse::<i32>().ok());
-
-        let voltage_now = fs::read_to_string(path.join("voltage_now"))
-            .ok()
-            .and_then(|s| s.trim().parse::<f32>().ok())
-            .map(|v| v / 1_000_000.0);
-
-        let current_now = fs::read_to_string(path.join("current_now"))
-            .ok()
-            .and_then(|s| s.trim().parse::<f32>().ok())
-            .map(|c| c / 1_000_000.0);
-
-        let power_now = fs::read_to_string(path.join("power_now"))
-            .ok()
-            .and_then(|s| s.trim().parse::<f32>().ok())
-            .map(|p| p / 1_000_000.0);
-
-        let technology = fs::read_to_string(path.join("technology"))
-            .ok()
-            .map(|s| s.trim().to_string());
-
-        let manufacturer = fs::read_to_string(path.join("manufacturer"))
-            .ok()
-            .map(|s| s.trim().to_string());
-
-        let model = fs::read_to_string(path.join("model_name"))
-            .ok()
-            .map(|s| s.trim().to_string());
-
-        let serial_number = fs::read_to_string(path.join("serial_number"))
-            .ok()
-            .map(|s| s.trim().to_string());
-
-        let (health, capacity_full, capacity_design) = {
-            let energy_full = fs::read_to_string(path.join("energy_full"))
-                .ok()
-                .and_then(|s| s.trim().parse::<f32>().ok());
-            
-            let energy_full_design = fs::read_to_string(path.join("energy_full_design"))
-                .ok()
-                .and_then(|s| s.trim().parse::<f32>().ok());
-
-            if let (Some(full), Some(design)) = (energy_full, energy_full_design) {
-                let health_val = if design > 0.0 {
-                    Some(((full / design) * 100.0).round() as i32)
-                } else {
-                    None
-                };
-                (health_val, Some((full / 1_000_000.0) as i32), Some((design / 1_000_000.0) as i32))
-            } else {
-                let charge_full = fs::read_to_string(path.join("charge_full"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<f32>().ok());
-                
-                let charge_full_design = fs::read_to_string(path.join("charge_full_design"))
-                    .ok()
-                    .and_then(|s| s.trim().parse::<f32>().ok());
-
-                if let (Some(full), Some(design)) = (charge_full, charge_full_design) {
-                    let health_val = if design > 0.0 {
-                        Some(((full / design) * 100.0).round() as i32)
-                    } else {
-                        None
-                    };
-                    (health_val, Some((full / 1_000_000.0) as i32), Some((design / 1_000_000.0) as i32))
-                } else {
-                    (None, None, None)
-                }
-            }
-        };
-
-        let time_to_empty = fs::read_to_string(path.join("time_to_empty_now"))
-            .ok()
-            .and_then(|s| s.trim().parse::<i32>().ok());
-
-        let time_to_full = fs::read_to_string(path.join("time_to_full_now"))
-            .ok()
-            .and_then(|s| s.trim().parse::<i32>().ok());
-        
-        return Some(BatteryInfo {
-            percent,
-            health: health.map(|h| h.clamp(0, 100)),
-            status,
-            cycle_count,
-            voltage_now,
-            current_now,
-            power_now,
-            technology,
-            manufacturer,
-            model,
-            serial_number,
-            capacity_full,
-            capacity_design,
-            time_to_empty,
-            time_to_full,
-        });
-    }
-    
-    None
+    }
+
+    Some(combined)
 }
 
-#[cfg(not(any(windows, target_os = "linux")))]
-pub fn get_battery_info() -> Option<BatteryInfo> {
-    None
+pub(crate) fn exact_percent(now: f32, full: f32) -> Option<f32> {
+    if full > 0.0 {
+        Some((now / full * 100.0).clamp(0.0, 100.0))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn health_percent(full: f32, design: f32) -> Option<i32> {
+    if design > 0.0 {
+        Some((((full / design) * 100.0).round() as i32).clamp(0, 100))
+    } else {
+        None
+    }
+}
+
+fn sum_all<T: std::iter::Sum<T>>(values: impl Iterator<Item = Option<T>>) -> Option<T> {
+    values
+        .collect::<Option<Vec<T>>>()
+        .map(|v| v.into_iter().sum())
+}
+
+fn sum_any<T: std::iter::Sum<T>>(values: impl Iterator<Item = Option<T>>) -> Option<T> {
+    let present: Vec<T> = values.flatten().collect();
+    if present.is_empty() {
+        None
+    } else {
+        Some(present.into_iter().sum())
+    }
+}
+
+fn shared_value<'a>(mut values: impl Iterator<Item = &'a Option<String>>) -> Option<String> {
+    let first = values.next()?.clone()?;
+    if values.all(|v| v.as_deref() == Some(first.as_str())) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(name: &str, percent: i32, now: f32, full: f32, design: f32) -> BatteryInfo {
+        let mut info = BatteryInfo::new(name, percent);
+        info.capacity_unit = Some(CapacityUnit::Energy);
+        info.energy_now_wh = Some(now);
+        info.energy_full_wh = Some(full);
+        info.energy_full_design_wh = Some(design);
+        info
+    }
+
+    #[test]
+    fn combine_empty_and_single() {
+        assert!(combine_batteries(&[]).is_none());
+
+        let single = battery("BAT0", 80, 40.0, 50.0, 60.0);
+        let combined = combine_batteries(&[single]).unwrap();
+        assert_eq!(combined.name, "BAT0");
+        assert_eq!(combined.percent, 80);
+    }
+
+    #[test]
+    fn combine_weights_by_capacity() {
+        let mut bat0 = battery("BAT0", 90, 45.0, 50.0, 50.0);
+        bat0.status = Some("Discharging".to_string());
+        bat0.power_now = Some(10.0);
+        bat0.technology = Some("Li-ion".to_string());
+        let mut bat1 = battery("BAT1", 30, 7.5, 25.0, 50.0);
+        bat1.status = Some("Unknown".to_string());
+        bat1.technology = Some("Li-poly".to_string());
+
+        let combined = combine_batteries(&[bat0, bat1]).unwrap();
+        assert_eq!(combined.name, "BAT0+BAT1");
+        assert_eq!(combined.percent, 70);
+        assert_eq!(combined.percent_exact, Some(70.0));
+        assert_eq!(combined.health, Some(75));
+        assert_eq!(combined.energy_now_wh, Some(52.5));
+        assert_eq!(combined.status.as_deref(), Some("Discharging"));
+        assert_eq!(combined.capacity_unit, Some(CapacityUnit::Energy));
+        assert_eq!(combined.technology, None);
+        assert_eq!(combined.time_to_empty, Some(315));
+        assert_eq!(combined.time_method, Some(EstimateMethod::Power));
+    }
+
+    #[test]
+    fn combine_without_energy_averages_and_adds_times() {
+        let mut bat0 = BatteryInfo::new("BAT0", 80);
+        bat0.time_to_empty = Some(60);
+        bat0.time_method = Some(EstimateMethod::Kernel);
+        let mut bat1 = BatteryInfo::new("BAT1", 41);
+        bat1.time_to_empty = Some(30);
+
+        let combined = combine_batteries(&[bat0, bat1]).unwrap();
+        assert_eq!(combined.percent, 61);
+        assert_eq!(combined.percent_exact, None);
+        assert_eq!(combined.health, None);
+        assert_eq!(combined.time_to_empty, Some(90));
+        assert_eq!(combined.time_method, Some(EstimateMethod::Kernel));
+    }
 }
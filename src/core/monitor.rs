@@ -0,0 +1,278 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    combine_batteries, BatteryError, BatteryInfo, BatterySource, DeviceBatteryInfo, PowerEvent,
+    PowerEvents, PowerSourceInfo, TimeEstimator,
+};
+use crate::i18n::messages;
+
+// Lectura completa del hardware en un instante, compartida por todos los
+// consumidores del monitor.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub batteries: Vec<BatteryInfo>,
+    pub combined: Option<BatteryInfo>,
+    pub power_sources: Vec<PowerSourceInfo>,
+    pub devices: Vec<DeviceBatteryInfo>,
+    pub error: Option<Arc<BatteryError>>,
+    // Eventos de conexión que provocaron esta lectura fuera de intervalo.
+    pub events: Vec<PowerEvent>,
+}
+
+impl Snapshot {
+    // Batería indicada o, sin nombre, la vista combinada.
+    pub fn battery(&self, name: Option<&str>) -> Result<BatteryInfo, Arc<BatteryError>> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        match name {
+            Some(name) => self
+                .batteries
+                .iter()
+                .find(|b| b.name == name)
+                .cloned()
+                .ok_or_else(|| Arc::new(BatteryError::UnknownBattery(name.to_string()))),
+            None => self
+                .combined
+                .clone()
+                .ok_or_else(|| Arc::new(BatteryError::NoBattery)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum BatteryChange {
+    PercentChanged {
+        name: String,
+        from: i32,
+        to: i32,
+    },
+    StatusChanged {
+        name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    ThresholdCrossed {
+        name: String,
+        threshold: i32,
+        percent: i32,
+        rising: bool,
+    },
+}
+
+// Cambios que recibe un suscriptor. Los umbrales avisan al cruzarlos en
+// cualquiera de los dos sentidos.
+#[derive(Debug, Clone, Default)]
+pub struct ChangeFilter {
+    pub percent: bool,
+    pub status: bool,
+    pub thresholds: Vec<i32>,
+}
+
+impl ChangeFilter {
+    fn changes(&self, previous: &Snapshot, current: &Snapshot) -> Vec<BatteryChange> {
+        let mut changes = Vec::new();
+
+        for info in tracked(current) {
+            let Some(before) = tracked(previous).find(|b| b.name == info.name) else {
+                continue;
+            };
+
+            if self.percent && before.percent != info.percent {
+                changes.push(BatteryChange::PercentChanged {
+                    name: info.name.clone(),
+                    from: before.percent,
+                    to: info.percent,
+                });
+            }
+
+            if self.status && before.status != info.status {
+                changes.push(BatteryChange::StatusChanged {
+                    name: info.name.clone(),
+                    from: before.status.clone(),
+                    to: info.status.clone(),
+                });
+            }
+
+            for &threshold in &self.thresholds {
+                let falling = before.percent > threshold && info.percent <= threshold;
+                let rising = before.percent < threshold && info.percent >= threshold;
+                if falling || rising {
+                    changes.push(BatteryChange::ThresholdCrossed {
+                        name: info.name.clone(),
+                        threshold,
+                        percent: info.percent,
+                        rising,
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+}
+
+// Cada batería y, si hay varias, también la vista combinada.
+fn tracked(snapshot: &Snapshot) -> impl Iterator<Item = &BatteryInfo> {
+    let combined = snapshot
+        .combined
+        .as_ref()
+        .filter(|_| snapshot.batteries.len() > 1);
+    snapshot.batteries.iter().chain(combined)
+}
+
+#[derive(Default)]
+struct Subscribers {
+    snapshots: Vec<Sender<Arc<Snapshot>>>,
+    changes: Vec<(ChangeFilter, Sender<BatteryChange>)>,
+}
+
+// La media móvil cubre el tiempo de este número de intervalos, aunque los
+// eventos de conexión provoquen lecturas adicionales.
+const SMOOTHING_SAMPLES: u32 = 10;
+
+// Lee el hardware en un único hilo a intervalos regulares (y en cuanto
+// llega un evento de conexión) y reparte cada lectura entre la CLI, la GUI
+// y la API, de modo que todas muestran los mismos datos.
+pub struct BatteryMonitor {
+    source: Arc<dyn BatterySource>,
+    latest: Arc<Mutex<Arc<Snapshot>>>,
+    subscribers: Arc<Mutex<Subscribers>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl BatteryMonitor {
+    pub fn start(source: Arc<dyn BatterySource>, interval: Duration) -> Self {
+        let mut estimator = TimeEstimator::new(interval.saturating_mul(SMOOTHING_SAMPLES));
+        let first = sample(source.as_ref(), &mut estimator, Vec::new());
+
+        let monitor = Self {
+            source: source.clone(),
+            latest: Arc::new(Mutex::new(Arc::new(first))),
+            subscribers: Arc::new(Mutex::new(Subscribers::default())),
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+
+        let latest = monitor.latest.clone();
+        let subscribers = monitor.subscribers.clone();
+        let stop = monitor.stop.clone();
+        thread::Builder::new()
+            .name("rusttery-monitor".to_string())
+            .spawn(move || {
+                let mut events = source.power_events().ok();
+                loop {
+                    let pending = wait(&mut events, interval);
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+
+                    let snapshot = Arc::new(sample(source.as_ref(), &mut estimator, pending));
+                    let previous =
+                        std::mem::replace(&mut *latest.lock().unwrap(), snapshot.clone());
+
+                    let mut subscribers = subscribers.lock().unwrap();
+                    subscribers
+                        .snapshots
+                        .retain(|sender| sender.send(snapshot.clone()).is_ok());
+                    subscribers.changes.retain(|(filter, sender)| {
+                        filter
+                            .changes(&previous, &snapshot)
+                            .into_iter()
+                            .all(|change| sender.send(change).is_ok())
+                    });
+                }
+            })
+            .unwrap_or_else(|e| panic!("{}: {}", messages().monitor_thread_failed, e));
+
+        monitor
+    }
+
+    pub fn source(&self) -> &dyn BatterySource {
+        self.source.as_ref()
+    }
+
+    pub fn latest(&self) -> Arc<Snapshot> {
+        self.latest.lock().unwrap().clone()
+    }
+
+    pub fn subscribe(&self) -> Receiver<Arc<Snapshot>> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.lock().unwrap().snapshots.push(sender);
+        receiver
+    }
+
+    pub fn subscribe_changes(&self, filter: ChangeFilter) -> Receiver<BatteryChange> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap()
+            .changes
+            .push((filter, sender));
+        receiver
+    }
+}
+
+impl Drop for BatteryMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+// Espera al siguiente intervalo o, si hay eventos de conexión disponibles,
+// hasta que llegue el primero.
+fn wait(events: &mut Option<PowerEvents>, interval: Duration) -> Vec<PowerEvent> {
+    match events
+        .as_ref()
+        .map(|receiver| receiver.recv_timeout(interval))
+    {
+        Some(Ok(event)) => {
+            let mut pending = vec![event];
+            pending.extend(events.iter().flat_map(|receiver| receiver.try_iter()));
+            pending
+        }
+        Some(Err(RecvTimeoutError::Timeout)) => Vec::new(),
+        Some(Err(RecvTimeoutError::Disconnected)) => {
+            *events = None;
+            thread::sleep(interval);
+            Vec::new()
+        }
+        None => {
+            thread::sleep(interval);
+            Vec::new()
+        }
+    }
+}
+
+fn sample(
+    source: &dyn BatterySource,
+    estimator: &mut TimeEstimator,
+    events: Vec<PowerEvent>,
+) -> Snapshot {
+    let mut snapshot = Snapshot {
+        power_sources: source.power_sources().unwrap_or_default(),
+        devices: source.device_batteries().unwrap_or_default(),
+        events,
+        ..Snapshot::default()
+    };
+
+    match source.batteries() {
+        Ok(mut batteries) => {
+            for info in batteries.iter_mut() {
+                estimator.apply(info);
+            }
+            snapshot.combined = combine_batteries(&batteries);
+            snapshot.batteries = batteries;
+        }
+        Err(e) => snapshot.error = Some(Arc::new(e)),
+    }
+
+    snapshot
+}
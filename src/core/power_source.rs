@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSourceKind {
+    Mains,
+    Usb,
+    UsbPd,
+}
+
+impl PowerSourceKind {
+    // Valores del atributo `type` de sysfs que corresponden a cargadores.
+    pub fn from_sysfs_type(value: &str) -> Option<Self> {
+        match value {
+            "Mains" => Some(PowerSourceKind::Mains),
+            "USB_PD" | "USB_PD_DRP" => Some(PowerSourceKind::UsbPd),
+            t if t == "USB" || t.starts_with("USB_") => Some(PowerSourceKind::Usb),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PowerSourceInfo {
+    pub name: String,
+    pub kind: PowerSourceKind,
+    pub online: bool,
+    pub voltage_now: Option<f32>,
+    pub voltage_max: Option<f32>,
+    pub current_max: Option<f32>,
+    pub usb_type: Option<String>,
+}
+
+impl PowerSourceInfo {
+    pub fn new(name: impl Into<String>, kind: PowerSourceKind, online: bool) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            online,
+            voltage_now: None,
+            voltage_max: None,
+            current_max: None,
+            usb_type: None,
+        }
+    }
+
+    pub fn power_max(&self) -> Option<f32> {
+        Some(self.voltage_max.or(self.voltage_now)? * self.current_max?)
+    }
+}
+
+// `usb_type` lista los modos soportados y marca entre corchetes el
+// negociado, p. ej. "C [PD] PD_PPS".
+pub fn negotiated_usb_type(value: &str) -> Option<String> {
+    value
+        .split_whitespace()
+        .find(|t| t.starts_with('[') && t.ends_with(']'))
+        .map(|t| t.trim_matches(|c| c == '[' || c == ']').to_string())
+}
+
+pub fn on_external_power(sources: &[PowerSourceInfo]) -> Option<bool> {
+    if sources.is_empty() {
+        None
+    } else {
+        Some(sources.iter().any(|s| s.online))
+    }
+}
@@ -0,0 +1,712 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[cfg(target_os = "linux")]
+use super::hotplug;
+use super::power_source::negotiated_usb_type;
+use super::threshold::parse_behaviour;
+use super::uevent::{parse_uevent, ReadMethod};
+use super::{
+    estimate_times, exact_percent, health_percent, BatteryError, BatteryInfo, BatterySource,
+    CapacityUnit, ChargeThresholds, DeviceBatteryInfo, PowerEvents, PowerSourceInfo,
+    PowerSourceKind, ThresholdUpdate,
+};
+
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys/class/power_supply";
+
+const START_THRESHOLD: &str = "charge_control_start_threshold";
+const END_THRESHOLD: &str = "charge_control_end_threshold";
+const CHARGE_BEHAVIOUR: &str = "charge_behaviour";
+
+pub struct SysfsSource {
+    root: PathBuf,
+}
+
+impl SysfsSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for SysfsSource {
+    fn default() -> Self {
+        Self::new(DEFAULT_SYSFS_ROOT)
+    }
+}
+
+impl SysfsSource {
+    fn entries(&self) -> Result<Vec<PathBuf>, BatteryError> {
+        let entries =
+            fs::read_dir(&self.root).map_err(|source| BatteryError::SysfsUnavailable {
+                path: self.root.clone(),
+                source,
+            })?;
+
+        let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn battery_dir(&self, battery: &str) -> Result<PathBuf, BatteryError> {
+        let path = self.root.join(battery);
+        let is_battery =
+            !battery.contains('/') && read_optional(&path, "type").as_deref() == Some("Battery");
+        if is_battery {
+            Ok(path)
+        } else {
+            Err(BatteryError::UnknownBattery(battery.to_string()))
+        }
+    }
+}
+
+impl BatterySource for SysfsSource {
+    // Una entrada ilegible no impide informar del resto; su error solo se
+    // devuelve si no se ha podido leer ninguna batería.
+    fn batteries(&self) -> Result<Vec<BatteryInfo>, BatteryError> {
+        let mut batteries = Vec::new();
+        let mut first_error = None;
+        for path in &self.entries()? {
+            let attrs = Attributes::load(path);
+            let entry_type = match attrs.required("type") {
+                Ok(entry_type) => entry_type,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            if entry_type != "Battery" {
+                continue;
+            }
+            // Las bahías vacías siguen apareciendo, pero sin datos legibles.
+            if attrs.optional("present").as_deref() == Some("0") {
+                continue;
+            }
+            if is_device_scope(&attrs) {
+                continue;
+            }
+            match read_battery(path, &attrs) {
+                Ok(battery) => batteries.push(battery),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if batteries.is_empty() => Err(e),
+            _ => Ok(batteries),
+        }
+    }
+
+    fn power_sources(&self) -> Result<Vec<PowerSourceInfo>, BatteryError> {
+        let mut sources = Vec::new();
+        for path in &self.entries()? {
+            let attrs = Attributes::load(path);
+            let Some(kind) = attrs
+                .optional("type")
+                .and_then(|t| PowerSourceKind::from_sysfs_type(&t))
+            else {
+                continue;
+            };
+            sources.push(read_power_source(path, &attrs, kind));
+        }
+
+        Ok(sources)
+    }
+
+    fn device_batteries(&self) -> Result<Vec<DeviceBatteryInfo>, BatteryError> {
+        let mut devices = Vec::new();
+        for path in &self.entries()? {
+            let attrs = Attributes::load(path);
+            if attrs.optional("type").as_deref() != Some("Battery") || !is_device_scope(&attrs) {
+                continue;
+            }
+
+            let mut device = DeviceBatteryInfo::new(entry_name(path));
+            device.scope = attrs.optional("scope");
+            device.model = attrs.optional("model_name");
+            device.manufacturer = attrs.optional("manufacturer");
+            device.serial_number = attrs.optional("serial_number");
+            device.percent = attrs.parse::<i32>("capacity").map(|c| c.clamp(0, 100));
+            device.capacity_level = attrs.optional("capacity_level");
+            device.status = attrs.optional("status");
+            devices.push(device);
+        }
+
+        Ok(devices)
+    }
+
+    fn charge_thresholds(&self, battery: &str) -> Result<ChargeThresholds, BatteryError> {
+        let path = self.battery_dir(battery)?;
+        let start = parse_optional::<u8>(&path, START_THRESHOLD);
+        let end = parse_optional::<u8>(&path, END_THRESHOLD);
+        let (behaviour, available_behaviours) = read_optional(&path, CHARGE_BEHAVIOUR)
+            .map(|b| parse_behaviour(&b))
+            .unwrap_or_default();
+
+        if start.is_none() && end.is_none() && behaviour.is_none() {
+            return Err(BatteryError::ThresholdUnsupported {
+                battery: battery.to_string(),
+                attribute: END_THRESHOLD,
+            });
+        }
+
+        Ok(ChargeThresholds {
+            battery: battery.to_string(),
+            start,
+            end,
+            behaviour,
+            available_behaviours,
+        })
+    }
+
+    fn set_charge_thresholds(
+        &self,
+        battery: &str,
+        update: &ThresholdUpdate,
+    ) -> Result<ChargeThresholds, BatteryError> {
+        let current = self.charge_thresholds(battery)?;
+        update.validate(&current)?;
+
+        let path = self.battery_dir(battery)?;
+        let mut writes = Vec::new();
+        if let Some(start) = update.start {
+            writes.push((START_THRESHOLD, start.to_string()));
+        }
+        if let Some(end) = update.end {
+            writes.push((END_THRESHOLD, end.to_string()));
+        }
+
+        // Al subir el inicio por encima del fin actual hay que escribir
+        // primero el fin, o el firmware rechaza el cambio.
+        if let (Some(start), Some(end)) = (update.start, current.end) {
+            if start >= end {
+                writes.reverse();
+            }
+        }
+
+        if let Some(behaviour) = &update.behaviour {
+            writes.push((CHARGE_BEHAVIOUR, behaviour.clone()));
+        }
+
+        for (attribute, value) in writes {
+            let file = path.join(attribute);
+            if !file.exists() {
+                return Err(BatteryError::ThresholdUnsupported {
+                    battery: battery.to_string(),
+                    attribute,
+                });
+            }
+            fs::write(&file, value).map_err(|source| BatteryError::Write { path: file, source })?;
+        }
+
+        self.charge_thresholds(battery)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn power_events(&self) -> Result<PowerEvents, BatteryError> {
+        hotplug::listen(&self.root).map_err(|source| BatteryError::Hotplug { source })
+    }
+}
+
+// Atributos de una entrada de power_supply. Se leen de una vez desde
+// `uevent`, ya que en algunos controladores ACPI cada lectura de archivo
+// supone una consulta al controlador embebido; si no está disponible se
+// lee archivo a archivo.
+struct Attributes<'a> {
+    dir: &'a Path,
+    uevent: Option<HashMap<String, String>>,
+}
+
+impl<'a> Attributes<'a> {
+    fn load(dir: &'a Path) -> Self {
+        let uevent = fs::read_to_string(dir.join("uevent"))
+            .ok()
+            .map(|content| parse_uevent(&content))
+            .filter(|values| !values.is_empty());
+        Self { dir, uevent }
+    }
+
+    fn method(&self) -> ReadMethod {
+        if self.uevent.is_some() {
+            ReadMethod::Uevent
+        } else {
+            ReadMethod::Files
+        }
+    }
+
+    // Muchos controladores no incluyen en uevent todos los atributos (temp,
+    // cycle_count...), así que los que falten se leen de su archivo.
+    fn optional(&self, attribute: &str) -> Option<String> {
+        match self
+            .uevent
+            .as_ref()
+            .and_then(|values| values.get(attribute))
+        {
+            Some(value) => Some(value.clone()),
+            None => read_optional(self.dir, attribute),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, attribute: &str) -> Option<T> {
+        self.optional(attribute).and_then(|s| s.parse().ok())
+    }
+
+    // Igual que optional, pero si falta el archivo da el error de lectura
+    // preciso.
+    fn required(&self, attribute: &str) -> Result<String, BatteryError> {
+        match self
+            .uevent
+            .as_ref()
+            .and_then(|values| values.get(attribute))
+        {
+            Some(value) => Ok(value.clone()),
+            None => read_required(self.dir, attribute),
+        }
+    }
+
+    fn parse_required<T: FromStr>(&self, attribute: &str) -> Result<T, BatteryError> {
+        let value = self.required(attribute)?;
+        let in_uevent = self
+            .uevent
+            .as_ref()
+            .is_some_and(|values| values.contains_key(attribute));
+        value.parse().map_err(|_| BatteryError::Parse {
+            path: self.dir.join(if in_uevent { "uevent" } else { attribute }),
+            value,
+        })
+    }
+}
+
+fn is_device_scope(attrs: &Attributes) -> bool {
+    attrs.optional("scope").as_deref() == Some("Device")
+}
+
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn read_power_source(path: &Path, attrs: &Attributes, kind: PowerSourceKind) -> PowerSourceInfo {
+    let usb_type = attrs
+        .optional("usb_type")
+        .and_then(|t| negotiated_usb_type(&t));
+
+    // Los puertos USB-C modernos se declaran como "USB" y solo usb_type
+    // indica que se ha negociado Power Delivery.
+    let kind = match (kind, usb_type.as_deref()) {
+        (PowerSourceKind::Usb, Some(t)) if t.starts_with("PD") => PowerSourceKind::UsbPd,
+        _ => kind,
+    };
+
+    let mut info = PowerSourceInfo::new(
+        entry_name(path),
+        kind,
+        attrs.optional("online").as_deref() == Some("1"),
+    );
+    info.voltage_now = attrs.parse::<f32>("voltage_now").map(|v| v / 1_000_000.0);
+    info.voltage_max = attrs.parse::<f32>("voltage_max").map(|v| v / 1_000_000.0);
+    info.current_max = attrs.parse::<f32>("current_max").map(|c| c / 1_000_000.0);
+    info.usb_type = usb_type;
+    info
+}
+
+fn read_required(dir: &Path, attribute: &str) -> Result<String, BatteryError> {
+    let path = dir.join(attribute);
+    fs::read_to_string(&path)
+        .map(|s| s.trim().to_string())
+        .map_err(|source| BatteryError::Read { path, source })
+}
+
+fn read_optional(dir: &Path, attribute: &str) -> Option<String> {
+    fs::read_to_string(dir.join(attribute))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn parse_optional<T: FromStr>(dir: &Path, attribute: &str) -> Option<T> {
+    read_optional(dir, attribute).and_then(|s| s.parse().ok())
+}
+
+fn read_battery(path: &Path, attrs: &Attributes) -> Result<BatteryInfo, BatteryError> {
+    let name = entry_name(path);
+
+    let status = attrs.optional("status");
+
+    let cycle_count = attrs.parse::<i32>("cycle_count");
+
+    let voltage_now = attrs.parse::<f32>("voltage_now").map(|v| v / 1_000_000.0);
+
+    let current_now = attrs.parse::<f32>("current_now").map(|c| c / 1_000_000.0);
+
+    let power_now = attrs.parse::<f32>("power_now").map(|p| p / 1_000_000.0);
+
+    let technology = attrs.optional("technology");
+
+    let manufacturer = attrs.optional("manufacturer");
+
+    let model = attrs.optional("model_name");
+
+    let serial_number = attrs.optional("serial_number");
+
+    let voltage_min_design = attrs
+        .parse::<f32>("voltage_min_design")
+        .map(|v| v / 1_000_000.0);
+
+    // energy_* viene en µWh y charge_* en µAh; cada batería expone solo uno
+    // de los dos grupos. Si solo hay carga, la energía se deriva con el
+    // voltaje mínimo de diseño.
+    let energy_now_wh = attrs.parse::<f32>("energy_now").map(|e| e / 1_000_000.0);
+    let energy_full_wh = attrs.parse::<f32>("energy_full").map(|e| e / 1_000_000.0);
+    let energy_full_design_wh = attrs
+        .parse::<f32>("energy_full_design")
+        .map(|e| e / 1_000_000.0);
+    let charge_now_ah = attrs.parse::<f32>("charge_now").map(|c| c / 1_000_000.0);
+    let charge_full_ah = attrs.parse::<f32>("charge_full").map(|c| c / 1_000_000.0);
+    let charge_full_design_ah = attrs
+        .parse::<f32>("charge_full_design")
+        .map(|c| c / 1_000_000.0);
+
+    let (capacity_unit, health) = match (energy_full_wh, energy_full_design_wh) {
+        (Some(full), Some(design)) => (Some(CapacityUnit::Energy), health_percent(full, design)),
+        _ => match (charge_full_ah, charge_full_design_ah) {
+            (Some(full), Some(design)) => {
+                (Some(CapacityUnit::Charge), health_percent(full, design))
+            }
+            _ => (None, None),
+        },
+    };
+
+    // Porcentaje fraccionario calculado en la unidad nativa, antes de
+    // cualquier conversión.
+    let percent_exact = match (energy_now_wh, energy_full_wh) {
+        (Some(now), Some(full)) => exact_percent(now, full),
+        _ => match (charge_now_ah, charge_full_ah) {
+            (Some(now), Some(full)) => exact_percent(now, full),
+            _ => None,
+        },
+    };
+
+    // El archivo capacity solo es imprescindible si no hay lecturas now/full.
+    let percent = match (attrs.parse::<i32>("capacity"), percent_exact) {
+        (Some(capacity), _) => capacity.clamp(0, 100),
+        (None, Some(exact)) => exact.round() as i32,
+        (None, None) => attrs.parse_required::<i32>("capacity")?.clamp(0, 100),
+    };
+
+    let to_energy = |charge: Option<f32>| match (charge, voltage_min_design) {
+        (Some(ah), Some(v)) => Some(ah * v),
+        _ => None,
+    };
+    let energy_now_wh = energy_now_wh.or_else(|| to_energy(charge_now_ah));
+    let energy_full_wh = energy_full_wh.or_else(|| to_energy(charge_full_ah));
+    let energy_full_design_wh = energy_full_design_wh.or_else(|| to_energy(charge_full_design_ah));
+
+    // El kernel informa los tiempos en segundos.
+    let time_to_empty = attrs.parse::<i32>("time_to_empty_now").map(|t| t / 60);
+
+    let time_to_full = attrs.parse::<i32>("time_to_full_now").map(|t| t / 60);
+
+    // temp viene en décimas de grado Celsius.
+    let temperature = attrs.parse::<f32>("temp").map(|t| t / 10.0);
+
+    let capacity_level = attrs.optional("capacity_level");
+
+    // alarm usa la misma unidad que energy_* o charge_* (µWh o µAh).
+    let alarm = attrs.parse::<f32>("alarm").map(|a| a / 1_000_000.0);
+
+    let present = attrs.optional("present").map(|p| p == "1");
+
+    let health_status = attrs.optional("health");
+
+    let mut info = BatteryInfo {
+        name,
+        percent,
+        percent_exact,
+        health,
+        status,
+        cycle_count,
+        voltage_now,
+        current_now,
+        power_now,
+        technology,
+        manufacturer,
+        model,
+        serial_number,
+        capacity_unit,
+        energy_now_wh,
+        energy_full_wh,
+        energy_full_design_wh,
+        charge_now_ah,
+        charge_full_ah,
+        charge_full_design_ah,
+        voltage_min_design,
+        time_to_empty,
+        time_to_full,
+        time_method: None,
+        temperature,
+        capacity_level,
+        alarm,
+        present,
+        health_status,
+        read_method: Some(attrs.method()),
+    };
+
+    estimate_times(&mut info);
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::EstimateMethod;
+
+    fn entry(root: &Path, name: &str, attributes: &[(&str, &str)]) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for (attribute, value) in attributes {
+            fs::write(dir.join(attribute), format!("{}\n", value)).unwrap();
+        }
+    }
+
+    #[test]
+    fn reads_energy_battery() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("capacity", "80"),
+                ("energy_now", "40000000"),
+                ("energy_full", "50000000"),
+                ("energy_full_design", "60000000"),
+                ("power_now", "10000000"),
+                ("voltage_now", "12000000"),
+                ("temp", "315"),
+            ],
+        );
+
+        let batteries = SysfsSource::new(root.path()).batteries().unwrap();
+        assert_eq!(batteries.len(), 1);
+
+        let battery = &batteries[0];
+        assert_eq!(battery.name, "BAT0");
+        assert_eq!(battery.percent, 80);
+        assert_eq!(battery.percent_exact, Some(80.0));
+        assert_eq!(battery.health, Some(83));
+        assert_eq!(battery.capacity_unit, Some(CapacityUnit::Energy));
+        assert_eq!(battery.energy_now_wh, Some(40.0));
+        assert_eq!(battery.power_now, Some(10.0));
+        assert_eq!(battery.voltage_now, Some(12.0));
+        assert_eq!(battery.temperature, Some(31.5));
+        assert_eq!(battery.time_to_empty, Some(240));
+        assert_eq!(battery.read_method, Some(ReadMethod::Files));
+    }
+
+    #[test]
+    fn derives_energy_and_percent_from_charge() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT1",
+            &[
+                ("type", "Battery"),
+                ("status", "Charging"),
+                ("charge_now", "2000000"),
+                ("charge_full", "4000000"),
+                ("charge_full_design", "5000000"),
+                ("current_now", "1000000"),
+                ("voltage_min_design", "10000000"),
+            ],
+        );
+
+        let battery = &SysfsSource::new(root.path()).batteries().unwrap()[0];
+        assert_eq!(battery.percent, 50);
+        assert_eq!(battery.health, Some(80));
+        assert_eq!(battery.capacity_unit, Some(CapacityUnit::Charge));
+        assert_eq!(battery.energy_full_wh, Some(40.0));
+        assert_eq!(battery.time_to_full, Some(120));
+        assert_eq!(battery.time_method, Some(EstimateMethod::Current));
+    }
+
+    #[test]
+    fn prefers_uevent() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "10"),
+                (
+                    "uevent",
+                    "POWER_SUPPLY_NAME=BAT0\nPOWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_CAPACITY=55",
+                ),
+            ],
+        );
+
+        let battery = &SysfsSource::new(root.path()).batteries().unwrap()[0];
+        assert_eq!(battery.percent, 55);
+        assert_eq!(battery.read_method, Some(ReadMethod::Uevent));
+    }
+
+    #[test]
+    fn reads_files_missing_from_uevent() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("temp", "298"),
+                ("cycle_count", "412"),
+                (
+                    "uevent",
+                    "POWER_SUPPLY_NAME=BAT0\nPOWER_SUPPLY_TYPE=Battery\nPOWER_SUPPLY_CAPACITY=55",
+                ),
+            ],
+        );
+
+        let battery = &SysfsSource::new(root.path()).batteries().unwrap()[0];
+        assert_eq!(battery.percent, 55);
+        assert_eq!(battery.temperature, Some(29.8));
+        assert_eq!(battery.cycle_count, Some(412));
+        assert_eq!(battery.read_method, Some(ReadMethod::Uevent));
+    }
+
+    #[test]
+    fn separates_chargers_and_peripherals() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[("type", "Battery"), ("capacity", "70")],
+        );
+        entry(
+            root.path(),
+            "BAT1",
+            &[("type", "Battery"), ("present", "0")],
+        );
+        entry(root.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        entry(
+            root.path(),
+            "ucsi-source-psy-0",
+            &[
+                ("type", "USB"),
+                ("online", "0"),
+                ("usb_type", "C [PD] PD_PPS"),
+                ("voltage_max", "20000000"),
+                ("current_max", "3250000"),
+            ],
+        );
+        entry(
+            root.path(),
+            "hidpp_battery_0",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("capacity_level", "Normal"),
+                ("model_name", "MX Master"),
+            ],
+        );
+
+        let source = SysfsSource::new(root.path());
+
+        let batteries = source.batteries().unwrap();
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].name, "BAT0");
+
+        let sources = source.power_sources().unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].name, "AC");
+        assert_eq!(sources[0].kind, PowerSourceKind::Mains);
+        assert!(sources[0].online);
+        assert_eq!(sources[1].kind, PowerSourceKind::UsbPd);
+        assert_eq!(sources[1].usb_type.as_deref(), Some("PD"));
+        assert_eq!(sources[1].power_max(), Some(65.0));
+
+        let devices = source.device_batteries().unwrap();
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].model.as_deref(), Some("MX Master"));
+        assert_eq!(devices[0].percent, None);
+        assert_eq!(devices[0].capacity_level.as_deref(), Some("Normal"));
+    }
+
+    #[test]
+    fn skips_unreadable_entries() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[("type", "Battery"), ("capacity", "70")],
+        );
+        entry(
+            root.path(),
+            "BAT1",
+            &[("type", "Battery"), ("status", "Unknown")],
+        );
+        entry(root.path(), "hid-unknown", &[]);
+        entry(root.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        let source = SysfsSource::new(root.path());
+
+        let batteries = source.batteries().unwrap();
+        assert_eq!(batteries.len(), 1);
+        assert_eq!(batteries[0].name, "BAT0");
+        assert_eq!(source.power_sources().unwrap().len(), 1);
+        assert!(source.device_batteries().unwrap().is_empty());
+
+        // Sin ninguna batería legible se informa del fallo.
+        fs::remove_dir_all(root.path().join("BAT0")).unwrap();
+        assert!(matches!(source.batteries(), Err(BatteryError::Read { .. })));
+    }
+
+    #[test]
+    fn missing_root_is_an_error() {
+        let root = tempfile::tempdir().unwrap();
+        let source = SysfsSource::new(root.path().join("power_supply"));
+        assert!(matches!(
+            source.batteries(),
+            Err(BatteryError::SysfsUnavailable { .. })
+        ));
+    }
+
+    #[test]
+    fn writes_thresholds() {
+        let root = tempfile::tempdir().unwrap();
+        entry(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("capacity", "70"),
+                (START_THRESHOLD, "40"),
+                (END_THRESHOLD, "60"),
+                (CHARGE_BEHAVIOUR, "[auto] inhibit-charge"),
+            ],
+        );
+        let source = SysfsSource::new(root.path());
+
+        let current = source.charge_thresholds("BAT0").unwrap();
+        assert_eq!((current.start, current.end), (Some(40), Some(60)));
+        assert_eq!(current.behaviour.as_deref(), Some("auto"));
+
+        // El inicio supera el fin actual, así que el fin se escribe antes.
+        let update = ThresholdUpdate {
+            start: Some(70),
+            end: Some(80),
+            behaviour: None,
+        };
+        let updated = source.set_charge_thresholds("BAT0", &update).unwrap();
+        assert_eq!((updated.start, updated.end), (Some(70), Some(80)));
+
+        assert!(matches!(
+            source.charge_thresholds("AC"),
+            Err(BatteryError::UnknownBattery(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use super::BatteryError;
+use crate::i18n::messages;
+
+// Umbrales de carga de una batería (charge_control_start_threshold,
+// charge_control_end_threshold y charge_behaviour en sysfs).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChargeThresholds {
+    pub battery: String,
+    pub start: Option<u8>,
+    pub end: Option<u8>,
+    pub behaviour: Option<String>,
+    pub available_behaviours: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThresholdUpdate {
+    pub start: Option<u8>,
+    pub end: Option<u8>,
+    pub behaviour: Option<String>,
+}
+
+impl ThresholdUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none() && self.behaviour.is_none()
+    }
+
+    // Comprueba el cambio contra los valores actuales, ya que el firmware
+    // rechaza un inicio mayor o igual que el final.
+    pub fn validate(&self, current: &ChargeThresholds) -> Result<(), BatteryError> {
+        let t = messages();
+        if self.is_empty() {
+            return Err(BatteryError::InvalidThreshold(t.no_threshold.to_string()));
+        }
+
+        // Sin el valor actual la batería no tiene ese archivo.
+        let unsupported = |attribute| BatteryError::ThresholdUnsupported {
+            battery: current.battery.clone(),
+            attribute,
+        };
+        if self.start.is_some() && current.start.is_none() {
+            return Err(unsupported("charge_control_start_threshold"));
+        }
+        if self.end.is_some() && current.end.is_none() {
+            return Err(unsupported("charge_control_end_threshold"));
+        }
+        if self.behaviour.is_some() && current.behaviour.is_none() {
+            return Err(unsupported("charge_behaviour"));
+        }
+
+        for value in [self.start, self.end].into_iter().flatten() {
+            if value > 100 {
+                return Err(BatteryError::InvalidThreshold((t.threshold_out_of_range)(
+                    value,
+                )));
+            }
+        }
+
+        if let (Some(start), Some(end)) = (self.start.or(current.start), self.end.or(current.end)) {
+            if start >= end {
+                return Err(BatteryError::InvalidThreshold((t.threshold_order)(
+                    start, end,
+                )));
+            }
+        }
+
+        if let Some(behaviour) = &self.behaviour {
+            if !current.available_behaviours.contains(behaviour) {
+                return Err(BatteryError::InvalidThreshold((t.behaviour_unavailable)(
+                    behaviour,
+                    &current.available_behaviours.join(", "),
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// charge_behaviour lista los modos y marca el activo entre corchetes:
+// "[auto] inhibit-charge force-discharge".
+pub fn parse_behaviour(value: &str) -> (Option<String>, Vec<String>) {
+    let mut active = None;
+    let available = value
+        .split_whitespace()
+        .map(|token| {
+            let mode = token.trim_matches(|c| c == '[' || c == ']').to_string();
+            if token.starts_with('[') {
+                active = Some(mode.clone());
+            }
+            mode
+        })
+        .collect();
+    (active, available)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current() -> ChargeThresholds {
+        ChargeThresholds {
+            battery: "BAT0".to_string(),
+            start: Some(40),
+            end: Some(80),
+            behaviour: Some("auto".to_string()),
+            available_behaviours: vec!["auto".to_string(), "inhibit-charge".to_string()],
+        }
+    }
+
+    fn update(start: Option<u8>, end: Option<u8>, behaviour: Option<&str>) -> ThresholdUpdate {
+        ThresholdUpdate {
+            start,
+            end,
+            behaviour: behaviour.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_updates() {
+        assert!(update(Some(50), Some(90), None)
+            .validate(&current())
+            .is_ok());
+        assert!(update(Some(60), None, None).validate(&current()).is_ok());
+        assert!(update(None, None, Some("inhibit-charge"))
+            .validate(&current())
+            .is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_updates() {
+        let invalid = [
+            update(None, None, None),
+            update(None, Some(101), None),
+            update(Some(80), None, None),
+            update(Some(70), Some(60), None),
+            update(None, Some(30), None),
+            update(None, None, Some("force-discharge")),
+        ];
+        for update in invalid {
+            assert!(
+                matches!(
+                    update.validate(&current()),
+                    Err(BatteryError::InvalidThreshold(_))
+                ),
+                "{:?}",
+                update
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_missing_attributes() {
+        let current = ChargeThresholds {
+            start: None,
+            ..current()
+        };
+        assert!(matches!(
+            update(Some(50), None, None).validate(&current),
+            Err(BatteryError::ThresholdUnsupported {
+                attribute: "charge_control_start_threshold",
+                ..
+            })
+        ));
+        assert!(update(None, Some(90), None).validate(&current).is_ok());
+    }
+
+    #[test]
+    fn parses_behaviour() {
+        let (active, available) = parse_behaviour("auto [inhibit-charge] force-discharge");
+        assert_eq!(active.as_deref(), Some("inhibit-charge"));
+        assert_eq!(available, ["auto", "inhibit-charge", "force-discharge"]);
+
+        assert_eq!(parse_behaviour(""), (None, Vec::new()));
+    }
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// Cómo se obtuvieron los atributos de sysfs: de una sola lectura del
+// archivo `uevent` o abriendo un archivo por atributo.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadMethod {
+    Uevent,
+    Files,
+}
+
+// Convierte "POWER_SUPPLY_ENERGY_NOW=38150000" en ("energy_now", "38150000"),
+// que coincide con el nombre del archivo del atributo. Acepta tanto el
+// archivo uevent (una clave por línea) como los mensajes netlink (separados
+// por '\0').
+pub fn parse_uevent(content: &str) -> HashMap<String, String> {
+    content
+        .split(['\n', '\0'])
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let attribute = key.trim().strip_prefix("POWER_SUPPLY_")?;
+            Some((attribute.to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_and_netlink_formats() {
+        let file =
+            "POWER_SUPPLY_NAME=BAT0\nPOWER_SUPPLY_ENERGY_NOW= 38150000 \nDEVTYPE=power_supply\n";
+        let values = parse_uevent(file);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["name"], "BAT0");
+        assert_eq!(values["energy_now"], "38150000");
+
+        let message = "change@/devices/LNXSYSTM:00/ACPI0003:00/power_supply/AC\0ACTION=change\0POWER_SUPPLY_ONLINE=1\0";
+        let values = parse_uevent(message);
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["online"], "1");
+
+        assert!(parse_uevent("").is_empty());
+    }
+}
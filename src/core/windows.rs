@@ -0,0 +1,180 @@
+use windows::Devices::Enumeration::DeviceInformation;
+use windows::Devices::Power::Battery;
+use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+use super::{
+    estimate_times, exact_percent, health_percent, BatteryError, BatteryInfo, BatterySource,
+    CapacityUnit, PowerSourceInfo, PowerSourceKind,
+};
+
+#[derive(Default)]
+pub struct WindowsSource;
+
+impl WindowsSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl BatterySource for WindowsSource {
+    // Como en sysfs, un dispositivo ilegible no impide informar del resto;
+    // su error solo se devuelve si no se ha podido leer ninguna batería.
+    fn batteries(&self) -> Result<Vec<BatteryInfo>, BatteryError> {
+        let devices = Battery::GetDeviceSelector()
+            .and_then(|selector| DeviceInformation::FindAllAsyncAqsFilter(&selector))
+            .and_then(|op| op.get())
+            .map_err(|e| windows_error("DeviceInformation::FindAllAsync", e))?;
+
+        let mut batteries = Vec::new();
+        let mut first_error = None;
+        for (index, device) in devices.into_iter().enumerate() {
+            match read_device(index, &device) {
+                Ok(battery) => batteries.push(battery),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if batteries.is_empty() && first_error.is_none() {
+            // Sin dispositivos enumerados, la batería agregada es la única
+            // fuente; si no informa capacidad es que no hay batería.
+            let aggregate = Battery::AggregateBattery()
+                .map_err(|e| windows_error("Battery::AggregateBattery", e))?;
+            match read_battery("Aggregate".to_string(), &aggregate) {
+                Ok(info) => batteries.push(info),
+                Err(BatteryError::Windows { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        match first_error {
+            Some(e) if batteries.is_empty() => Err(e),
+            _ => Ok(batteries),
+        }
+    }
+
+    // Windows solo expone si hay corriente alterna, sin detalles del cargador.
+    fn power_sources(&self) -> Result<Vec<PowerSourceInfo>, BatteryError> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }
+            .map_err(|e| windows_error("GetSystemPowerStatus", e))?;
+
+        Ok(match status.ACLineStatus {
+            0 => vec![PowerSourceInfo::new("AC", PowerSourceKind::Mains, false)],
+            1 => vec![PowerSourceInfo::new("AC", PowerSourceKind::Mains, true)],
+            _ => Vec::new(),
+        })
+    }
+}
+
+fn read_device(index: usize, device: &DeviceInformation) -> Result<BatteryInfo, BatteryError> {
+    let id = device
+        .Id()
+        .map_err(|e| windows_error("DeviceInformation::Id", e))?;
+    let battery = Battery::FromIdAsync(&id)
+        .and_then(|op| op.get())
+        .map_err(|e| windows_error("Battery::FromIdAsync", e))?;
+    let name = device
+        .Name()
+        .map(|n| n.to_string())
+        .ok()
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("BAT{}", index));
+
+    read_battery(name, &battery)
+}
+
+fn windows_error(call: &'static str, error: windows::core::Error) -> BatteryError {
+    BatteryError::Windows {
+        call,
+        message: error.to_string(),
+    }
+}
+
+fn read_battery(name: String, battery: &Battery) -> Result<BatteryInfo, BatteryError> {
+    let report = battery
+        .GetReport()
+        .map_err(|e| windows_error("Battery::GetReport", e))?;
+
+    let full = report
+        .FullChargeCapacityInMilliwattHours()
+        .and_then(|r| r.Value())
+        .map_err(|e| windows_error("BatteryReport::FullChargeCapacityInMilliwattHours", e))?
+        as f32;
+
+    let remaining = report
+        .RemainingCapacityInMilliwattHours()
+        .and_then(|r| r.Value())
+        .map_err(|e| windows_error("BatteryReport::RemainingCapacityInMilliwattHours", e))?
+        as f32;
+
+    if full <= 0.0 {
+        return Err(BatteryError::ZeroCapacity { name });
+    }
+
+    let percent_exact = exact_percent(remaining, full);
+    let percent = ((remaining / full) * 100.0).round() as i32;
+
+    let design = report
+        .DesignCapacityInMilliwattHours()
+        .and_then(|r| r.Value())
+        .ok()
+        .map(|d| d as f32)
+        .filter(|d| *d > 0.0);
+
+    let health = design.and_then(|d| health_percent(full, d));
+
+    let status = if let Ok(status_ref) = report.Status() {
+        match status_ref.0 {
+            1 => Some("Discharging".to_string()),
+            2 => Some("Idle".to_string()),
+            3 => Some("Charging".to_string()),
+            _ => Some("Unknown".to_string()),
+        }
+    } else {
+        None
+    };
+
+    let charge_rate = report
+        .ChargeRateInMilliwatts()
+        .ok()
+        .and_then(|r| r.Value().ok())
+        .map(|v| v as f32 / 1000.0);
+
+    let mut info = BatteryInfo {
+        name,
+        percent: percent.clamp(0, 100),
+        percent_exact,
+        health,
+        status,
+        cycle_count: None,
+        voltage_now: None,
+        current_now: None,
+        power_now: charge_rate,
+        technology: None,
+        manufacturer: None,
+        model: None,
+        serial_number: None,
+        capacity_unit: Some(CapacityUnit::Energy),
+        energy_now_wh: Some(remaining / 1000.0),
+        energy_full_wh: Some(full / 1000.0),
+        energy_full_design_wh: design.map(|d| d / 1000.0),
+        charge_now_ah: None,
+        charge_full_ah: None,
+        charge_full_design_ah: None,
+        voltage_min_design: None,
+        time_to_empty: None,
+        time_to_full: None,
+        time_method: None,
+        temperature: None,
+        capacity_level: None,
+        alarm: None,
+        present: Some(true),
+        health_status: None,
+        read_method: None,
+    };
+
+    estimate_times(&mut info);
+    Ok(info)
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rusttery::core::{BatteryInfo, BatteryMonitor, Snapshot};
+use rusttery::database::Database;
+use rusttery::i18n::messages;
+
+use crate::config::{Config, DedupRule};
+
+// Cada cuánto se comprueba si ha llegado SIGTERM mientras se espera la
+// siguiente lectura.
+const STOP_CHECK: Duration = Duration::from_millis(500);
+
+// Recolector sin interfaz: guarda cada lectura del monitor (o solo las que
+// cambian, según `daemon.dedup`) hasta recibir SIGTERM o SIGINT. Bajo
+// systemd avisa cuando está listo y mantiene vivo el watchdog.
+pub fn run(monitor: &BatteryMonitor, config: &Config) -> i32 {
+    let t = messages();
+    let stop = Arc::new(AtomicBool::new(false));
+    for signal in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGINT] {
+        if let Err(e) = signal_hook::flag::register(signal, stop.clone()) {
+            eprintln!("{}", (t.signal_handler_failed)(&e));
+            return 1;
+        }
+    }
+
+    let db = match config.open_database() {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("{}", (t.database_open_failed)(&config.db_path.value, &e));
+            return 1;
+        }
+    };
+
+    let interval = Duration::from_secs(config.interval.value);
+    if let Some(watchdog) = systemd::watchdog_timeout() {
+        if interval * 2 > watchdog {
+            eprintln!(
+                "{}",
+                (t.watchdog_interval)(interval.as_secs(), watchdog.as_secs())
+            );
+        }
+    }
+
+    eprintln!(
+        "{}",
+        (t.daemon_started)(
+            interval.as_secs(),
+            &config.db_path.value,
+            &config.dedup.value
+        )
+    );
+
+    let snapshots = monitor.subscribe();
+    let mut recorder = Recorder::new(config.dedup.value);
+    let snapshot = monitor.latest();
+    recorder.record(&db, &snapshot);
+    systemd::ready(&recorder.status(&snapshot));
+
+    'run: loop {
+        let snapshot = loop {
+            if stop.load(Ordering::Relaxed) {
+                break 'run;
+            }
+            match snapshots.recv_timeout(STOP_CHECK) {
+                Ok(snapshot) => break snapshot,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    eprintln!("{}", t.monitor_stopped);
+                    systemd::stopping();
+                    return 1;
+                }
+            }
+        };
+
+        // El watchdog solo se renueva con lecturas nuevas, así que systemd
+        // reinicia el servicio si el monitor deja de leer.
+        recorder.record(&db, &snapshot);
+        systemd::watchdog(&recorder.status(&snapshot));
+    }
+
+    systemd::stopping();
+    eprintln!("{}", (t.daemon_stopped)(recorder.saved));
+    0
+}
+
+type ReadingKey = (i32, Option<i32>, Option<String>);
+
+struct Recorder {
+    dedup: DedupRule,
+    last: HashMap<String, ReadingKey>,
+    last_error: Option<String>,
+    saved: u64,
+}
+
+impl Recorder {
+    fn new(dedup: DedupRule) -> Self {
+        Self {
+            dedup,
+            last: HashMap::new(),
+            last_error: None,
+            saved: 0,
+        }
+    }
+
+    fn record(&mut self, db: &Database, snapshot: &Snapshot) {
+        let t = messages();
+        for event in &snapshot.events {
+            if let Err(e) = db.save_event(event) {
+                self.report((t.save_event_failed)(&e));
+            }
+        }
+
+        if let Some(e) = &snapshot.error {
+            self.report((t.battery_unreadable)(e));
+            return;
+        }
+
+        for info in &snapshot.batteries {
+            let key = self.key(info);
+            if key.is_some() && self.last.get(&info.name) == key.as_ref() {
+                continue;
+            }
+
+            match db.save_battery_info(info) {
+                Ok(()) => self.saved += 1,
+                Err(e) => {
+                    self.report((t.save_reading_failed)(&e));
+                    return;
+                }
+            }
+            if let Some(key) = key {
+                self.last.insert(info.name.clone(), key);
+            }
+        }
+
+        self.last_error = None;
+    }
+
+    // Valores que deben cambiar para guardar una nueva lectura; sin
+    // deduplicación se guardan todas.
+    fn key(&self, info: &BatteryInfo) -> Option<ReadingKey> {
+        match self.dedup {
+            DedupRule::Off => None,
+            DedupRule::Percent => Some((info.percent, None, None)),
+            DedupRule::Changes => Some((info.percent, info.health, info.status.clone())),
+        }
+    }
+
+    // Un error que se repite en cada lectura solo se escribe una vez.
+    fn report(&mut self, message: String) {
+        if self.last_error.as_ref() != Some(&message) {
+            eprintln!("{}", message);
+            self.last_error = Some(message);
+        }
+    }
+
+    fn status(&self, snapshot: &Snapshot) -> String {
+        let t = messages();
+        match &snapshot.combined {
+            Some(info) => (t.daemon_status)(&info.name, info.percent, self.saved),
+            None => (t.daemon_status_no_battery)(self.saved),
+        }
+    }
+}
+
+// Notificaciones a systemd (Type=notify). Fuera de systemd, o en otros
+// sistemas, no hacen nada.
+mod systemd {
+    use std::time::Duration;
+
+    #[cfg(target_os = "linux")]
+    use sd_notify::NotifyState;
+
+    #[cfg(target_os = "linux")]
+    pub fn ready(status: &str) {
+        let _ = sd_notify::notify(false, &[NotifyState::Ready, NotifyState::Status(status)]);
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn watchdog(status: &str) {
+        let _ = sd_notify::notify(false, &[NotifyState::Watchdog, NotifyState::Status(status)]);
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn stopping() {
+        let _ = sd_notify::notify(false, &[NotifyState::Stopping]);
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn watchdog_timeout() -> Option<Duration> {
+        let mut usec = 0;
+        sd_notify::watchdog_enabled(false, &mut usec).then(|| Duration::from_micros(usec))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn ready(_status: &str) {}
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn watchdog(_status: &str) {}
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn stopping() {}
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn watchdog_timeout() -> Option<Duration> {
+        None
+    }
+}
@@ -1,6 +1,9 @@
-use rusqlite::{Connection, Result};
-use crate::core::BatteryInfo;
-use std::path::PathBuf;
+use rusqlite::{Connection, OpenFlags, Result};
+use crate::core::{BatteryInfo, CapacityUnit, EstimateMethod, PowerEvent};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
 
 pub struct Database {
     conn: Connection,
@@ -8,14 +11,36 @@ pub struct Database {
 
 impl Database {
     pub fn new() -> Result<Self> {
-        let db_path = Self::get_db_path();
-        let conn = Connection::open(db_path)?;
-        
+        Self::open(Self::default_path())
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_retention(path, DEFAULT_RETENTION_DAYS)
+    }
+
+    // Al abrir se borran los registros más antiguos de `retention_days`
+    // días; con 0 se conserva todo el historial.
+    pub fn open_with_retention(path: impl AsRef<Path>, retention_days: u32) -> Result<Self> {
+        // El directorio solo se crea al abrir para escribir; si no se puede
+        // crear, Connection::open da el error.
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let conn = Connection::open(path)?;
+
+        // Con WAL se puede leer el historial (`rusttery history`, la API)
+        // mientras otro proceso, como `rusttery daemon`, sigue escribiendo;
+        // las escrituras simultáneas esperan en vez de fallar con SQLITE_BUSY.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+
         conn.execute(
             "CREATE TABLE IF NOT EXISTS battery_history (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 timestamp INTEGER NOT NULL,
+                battery TEXT,
                 percent INTEGER NOT NULL,
+                percent_exact REAL,
                 health INTEGER,
                 status TEXT,
                 cycle_count INTEGER,
@@ -26,10 +51,55 @@ impl Database {
                 manufacturer TEXT,
                 model TEXT,
                 serial_number TEXT,
-                capacity_full INTEGER,
-                capacity_design INTEGER,
+                capacity_unit TEXT,
+                energy_now_wh REAL,
+                energy_full_wh REAL,
+                energy_full_design_wh REAL,
+                charge_now_ah REAL,
+                charge_full_ah REAL,
+                charge_full_design_ah REAL,
+                voltage_min_design REAL,
                 time_to_empty INTEGER,
-                time_to_full INTEGER
+                time_to_full INTEGER,
+                time_method TEXT,
+                temperature REAL,
+                capacity_level TEXT,
+                alarm REAL,
+                present INTEGER,
+                health_status TEXT
+            )",
+            [],
+        )?;
+
+        Self::add_missing_columns(
+            &conn,
+            &[
+                ("battery", "TEXT"),
+                ("capacity_unit", "TEXT"),
+                ("energy_full_wh", "REAL"),
+                ("energy_full_design_wh", "REAL"),
+                ("charge_full_ah", "REAL"),
+                ("charge_full_design_ah", "REAL"),
+                ("voltage_min_design", "REAL"),
+                ("percent_exact", "REAL"),
+                ("energy_now_wh", "REAL"),
+                ("charge_now_ah", "REAL"),
+                ("time_method", "TEXT"),
+                ("temperature", "REAL"),
+                ("capacity_level", "TEXT"),
+                ("alarm", "REAL"),
+                ("present", "INTEGER"),
+                ("health_status", "TEXT"),
+            ],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS power_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                name TEXT NOT NULL,
+                status TEXT
             )",
             [],
         )?;
@@ -39,16 +109,51 @@ impl Database {
             [],
         )?;
 
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_battery_timestamp ON battery_history(battery, timestamp)",
+            [],
+        )?;
+
         let db = Database { conn };
-        let _ = db.cleanup_old_records(30);
+        if retention_days > 0 {
+            let _ = db.cleanup_old_records(retention_days.into());
+        }
         Ok(db)
     }
 
-    fn get_db_path() -> PathBuf {
+    // Solo para consultas: no crea el archivo ni toca el esquema, así que
+    // basta con permiso de lectura y una ruta inexistente es un error.
+    pub fn open_read_only(path: impl AsRef<Path>) -> Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+        Ok(Database { conn })
+    }
+
+    fn add_missing_columns(conn: &Connection, columns: &[(&str, &str)]) -> Result<()> {
+        let existing: Vec<String> = conn
+            .prepare("PRAGMA table_info(battery_history)")?
+            .query_map([], |row| row.get(1))?
+            .collect::<Result<_>>()?;
+
+        for (name, column_type) in columns {
+            if !existing.iter().any(|c| c == name) {
+                conn.execute(
+                    &format!("ALTER TABLE battery_history ADD COLUMN {} {}", name, column_type),
+                    [],
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn default_path() -> PathBuf {
         let mut path = dirs::data_local_dir()
             .unwrap_or_else(|| PathBuf::from("."));
         path.push("rusttery");
-        std::fs::create_dir_all(&path).ok();
         path.push("battery_history.db");
         path
     }
@@ -61,92 +166,184 @@ impl Database {
 
         self.conn.execute(
             "INSERT INTO battery_history (
-                timestamp, percent, health, status, cycle_count, 
-                voltage_now, current_now, power_now, technology,
-                manufacturer, model, serial_number, capacity_full,
-                capacity_design, time_to_empty, time_to_full
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
-            rusqlite::params![
-                timestamp,
-                info.percent,
-                info.health,
-                info.status,
-                info.cycle_count,
-                info.voltage_now,
-                info.current_now,
-                info.power_now,
-                info.technology,
-                info.manufacturer,
-                info.model,
-                info.serial_number,
-                info.capacity_full,
-                info.capacity_design,
-                info.time_to_empty,
-                info.time_to_full,
-            ],
+                timestamp, battery, percent, percent_exact, health, status,
+                cycle_count, voltage_now, current_now, power_now, technology,
+                manufacturer, model, serial_number, capacity_unit,
+                energy_now_wh, energy_full_wh, energy_full_design_wh,
+                charge_now_ah, charge_full_ah, charge_full_design_ah,
+                voltage_min_design, time_to_empty, time_to_full, time_method,
+                temperature, capacity_level, alarm, present, health_status
+            ) VALUES (
+                :timestamp, :battery, :percent, :percent_exact, :health, :status,
+                :cycle_count, :voltage_now, :current_now, :power_now, :technology,
+                :manufacturer, :model, :serial_number, :capacity_unit,
+                :energy_now_wh, :energy_full_wh, :energy_full_design_wh,
+                :charge_now_ah, :charge_full_ah, :charge_full_design_ah,
+                :voltage_min_design, :time_to_empty, :time_to_full, :time_method,
+                :temperature, :capacity_level, :alarm, :present, :health_status
+            )",
+            rusqlite::named_params! {
+                ":timestamp": timestamp,
+                ":battery": info.name,
+                ":percent": info.percent,
+                ":percent_exact": info.percent_exact,
+                ":health": info.health,
+                ":status": info.status,
+                ":cycle_count": info.cycle_count,
+                ":voltage_now": info.voltage_now,
+                ":current_now": info.current_now,
+                ":power_now": info.power_now,
+                ":technology": info.technology,
+                ":manufacturer": info.manufacturer,
+                ":model": info.model,
+                ":serial_number": info.serial_number,
+                ":capacity_unit": info.capacity_unit.map(|u| u.as_str()),
+                ":energy_now_wh": info.energy_now_wh,
+                ":energy_full_wh": info.energy_full_wh,
+                ":energy_full_design_wh": info.energy_full_design_wh,
+                ":charge_now_ah": info.charge_now_ah,
+                ":charge_full_ah": info.charge_full_ah,
+                ":charge_full_design_ah": info.charge_full_design_ah,
+                ":voltage_min_design": info.voltage_min_design,
+                ":time_to_empty": info.time_to_empty,
+                ":time_to_full": info.time_to_full,
+                ":time_method": info.time_method.map(|m| m.as_str()),
+                ":temperature": info.temperature,
+                ":capacity_level": info.capacity_level,
+                ":alarm": info.alarm,
+                ":present": info.present,
+                ":health_status": info.health_status,
+            },
         )?;
 
         Ok(())
     }
 
-    pub fn get_history(&self, hours: i64) -> Result<Vec<HistoryRecord>> {
+    pub fn save_event(&self, event: &PowerEvent) -> Result<()> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        self.conn.execute(
+            "INSERT INTO power_events (timestamp, event, name, status)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![timestamp, event.as_str(), event.name(), event.status()],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn get_events(&self, hours: i64) -> Result<Vec<EventRecord>> {
         let since = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64 - (hours * 3600);
 
         let mut stmt = self.conn.prepare(
-            "SELECT timestamp, percent, health, status, cycle_count,
-                    voltage_now, current_now, power_now, technology,
-                    manufacturer, model, serial_number, capacity_full,
-                    capacity_design, time_to_empty, time_to_full
-             FROM battery_history 
-             WHERE timestamp >= ?1 
+            "SELECT timestamp, event, name, status
+             FROM power_events
+             WHERE timestamp >= ?1
              ORDER BY timestamp ASC"
         )?;
 
         let records = stmt.query_map([since], |row| {
+            Ok(EventRecord {
+                timestamp: row.get("timestamp")?,
+                event: row.get("event")?,
+                name: row.get("name")?,
+                status: row.get("status")?,
+            })
+        })?;
+
+        records.collect()
+    }
+
+    pub fn get_history(&self, hours: i64, battery: Option<&str>) -> Result<Vec<HistoryRecord>> {
+        self.get_history_range(hours_ago(hours), None, battery)
+    }
+
+    // Registros entre dos marcas de tiempo Unix (ambas incluidas); sin
+    // `until` llega hasta el último registro.
+    pub fn get_history_range(
+        &self,
+        since: i64,
+        until: Option<i64>,
+        battery: Option<&str>,
+    ) -> Result<Vec<HistoryRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT *
+             FROM battery_history
+             WHERE timestamp >= ?1 AND (?2 IS NULL OR timestamp <= ?2)
+               AND (?3 IS NULL OR battery = ?3)
+             ORDER BY timestamp ASC"
+        )?;
+
+        let records = stmt.query_map(rusqlite::params![since, until, battery], |row| {
             Ok(HistoryRecord {
-                timestamp: row.get(0)?,
-                percent: row.get(1)?,
-                health: row.get(2)?,
-                status: row.get(3)?,
-                cycle_count: row.get(4)?,
-                voltage_now: row.get(5)?,
-                current_now: row.get(6)?,
-                power_now: row.get(7)?,
-                technology: row.get(8)?,
-                manufacturer: row.get(9)?,
-                model: row.get(10)?,
-                serial_number: row.get(11)?,
-                capacity_full: row.get(12)?,
-                capacity_design: row.get(13)?,
-                time_to_empty: row.get(14)?,
-                time_to_full: row.get(15)?,
+                timestamp: row.get("timestamp")?,
+                battery: row.get("battery")?,
+                percent: row.get("percent")?,
+                percent_exact: row.get("percent_exact")?,
+                health: row.get("health")?,
+                status: row.get("status")?,
+                cycle_count: row.get("cycle_count")?,
+                voltage_now: row.get("voltage_now")?,
+                current_now: row.get("current_now")?,
+                power_now: row.get("power_now")?,
+                technology: row.get("technology")?,
+                manufacturer: row.get("manufacturer")?,
+                model: row.get("model")?,
+                serial_number: row.get("serial_number")?,
+                capacity_unit: row
+                    .get::<_, Option<String>>("capacity_unit")?
+                    .and_then(|u| u.parse::<CapacityUnit>().ok()),
+                energy_now_wh: row.get("energy_now_wh")?,
+                energy_full_wh: row.get("energy_full_wh")?,
+                energy_full_design_wh: row.get("energy_full_design_wh")?,
+                charge_now_ah: row.get("charge_now_ah")?,
+                charge_full_ah: row.get("charge_full_ah")?,
+                charge_full_design_ah: row.get("charge_full_design_ah")?,
+                voltage_min_design: row.get("voltage_min_design")?,
+                time_to_empty: row.get("time_to_empty")?,
+                time_to_full: row.get("time_to_full")?,
+                time_method: row
+                    .get::<_, Option<String>>("time_method")?
+                    .and_then(|m| m.parse::<EstimateMethod>().ok()),
+                temperature: row.get("temperature")?,
+                capacity_level: row.get("capacity_level")?,
+                alarm: row.get("alarm")?,
+                present: row.get("present")?,
+                health_status: row.get("health_status")?,
             })
         })?;
 
         records.collect()
     }
 
-    pub fn get_statistics(&self, hours: i64) -> Result<Statistics> {
-        let since = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64 - (hours * 3600);
+    pub fn get_statistics(&self, hours: i64, battery: Option<&str>) -> Result<Statistics> {
+        self.get_statistics_range(hours_ago(hours), None, battery)
+    }
 
+    pub fn get_statistics_range(
+        &self,
+        since: i64,
+        until: Option<i64>,
+        battery: Option<&str>,
+    ) -> Result<Statistics> {
         let mut stmt = self.conn.prepare(
             "SELECT 
-                AVG(percent) as avg_percent,
+                AVG(COALESCE(percent_exact, percent)) as avg_percent,
                 MIN(percent) as min_percent,
                 MAX(percent) as max_percent,
                 AVG(power_now) as avg_power,
                 COUNT(*) as total_records
-             FROM battery_history 
-             WHERE timestamp >= ?1"
+             FROM battery_history
+             WHERE timestamp >= ?1 AND (?2 IS NULL OR timestamp <= ?2)
+               AND (?3 IS NULL OR battery = ?3)"
         )?;
 
-        let stats = stmt.query_row([since], |row| {
+        let stats = stmt.query_row(rusqlite::params![since, until, battery], |row| {
             Ok(Statistics {
                 avg_percent: row.get(0).unwrap_or(0.0),
                 min_percent: row.get(1).unwrap_or(0),
@@ -159,12 +356,30 @@ impl Database {
         Ok(stats)
     }
 
+    // Baterías con registros en el intervalo, por nombre.
+    pub fn get_batteries(&self, since: i64, until: Option<i64>) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT battery
+             FROM battery_history
+             WHERE battery IS NOT NULL AND timestamp >= ?1 AND (?2 IS NULL OR timestamp <= ?2)
+             ORDER BY battery ASC"
+        )?;
+
+        let names = stmt.query_map(rusqlite::params![since, until], |row| row.get(0))?;
+        names.collect()
+    }
+
     pub fn cleanup_old_records(&self, days: i64) -> Result<usize> {
         let cutoff = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_secs() as i64 - (days * 86400);
 
+        self.conn.execute(
+            "DELETE FROM power_events WHERE timestamp < ?1",
+            [cutoff],
+        )?;
+
         self.conn.execute(
             "DELETE FROM battery_history WHERE timestamp < ?1",
             [cutoff],
@@ -172,10 +387,19 @@ impl Database {
     }
 }
 
+fn hours_ago(hours: i64) -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64 - (hours * 3600)
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct HistoryRecord {
     pub timestamp: i64,
+    pub battery: Option<String>,
     pub percent: i32,
+    pub percent_exact: Option<f32>,
     pub health: Option<i32>,
     pub status: Option<String>,
     pub cycle_count: Option<i32>,
@@ -186,10 +410,30 @@ pub struct HistoryRecord {
     pub manufacturer: Option<String>,
     pub model: Option<String>,
     pub serial_number: Option<String>,
-    pub capacity_full: Option<i32>,
-    pub capacity_design: Option<i32>,
+    pub capacity_unit: Option<CapacityUnit>,
+    pub energy_now_wh: Option<f32>,
+    pub energy_full_wh: Option<f32>,
+    pub energy_full_design_wh: Option<f32>,
+    pub charge_now_ah: Option<f32>,
+    pub charge_full_ah: Option<f32>,
+    pub charge_full_design_ah: Option<f32>,
+    pub voltage_min_design: Option<f32>,
     pub time_to_empty: Option<i32>,
     pub time_to_full: Option<i32>,
+    pub time_method: Option<EstimateMethod>,
+    pub temperature: Option<f32>,
+    pub capacity_level: Option<String>,
+    pub alarm: Option<f32>,
+    pub present: Option<bool>,
+    pub health_status: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct EventRecord {
+    pub timestamp: i64,
+    pub event: String,
+    pub name: String,
+    pub status: Option<String>,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -200,3 +444,39 @@ pub struct Statistics {
     pub avg_power: f64,
     pub total_records: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_does_not_create_or_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+
+        assert!(Database::open_read_only(&path).is_err());
+        assert!(!path.exists());
+
+        let db = Database::open_with_retention(&path, 0).unwrap();
+        db.save_battery_info(&BatteryInfo::new("BAT0", 75)).unwrap();
+        drop(db);
+
+        let db = Database::open_read_only(&path).unwrap();
+        let history = db.get_history(1, Some("BAT0")).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].percent, 75);
+        assert!(db.save_battery_info(&BatteryInfo::new("BAT0", 70)).is_err());
+    }
+
+    #[test]
+    fn creates_the_directory_only_when_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rusttery").join("history.db");
+
+        assert!(Database::open_read_only(&path).is_err());
+        assert!(!path.parent().unwrap().exists());
+
+        Database::open_with_retention(&path, 0).unwrap();
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,479 @@
+// Interfaz C. Los objetos se entregan como punteros opacos que se liberan
+// con su función `*_free`; las cadenas devueltas pertenecen al llamante y
+// se liberan con `rusttery_string_free`. Los valores desconocidos se
+// representan con -1 (enteros) o NaN (reales).
+//
+// Todas las funciones aceptan punteros nulos en lugar de objetos, pero no
+// pueden comprobar punteros colgantes o de otro tipo: el llamante debe
+// pasar solo punteros obtenidos de esta misma interfaz y no usarlos
+// después de liberarlos.
+#![allow(clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
+
+use crate::core::{combine_batteries, default_source, BatteryError, BatteryInfo};
+use crate::database::{Database, HistoryRecord};
+use crate::i18n::messages;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustteryStatus {
+    Ok = 0,
+    NoBattery = 1,
+    UnknownBattery = 2,
+    ReadFailed = 3,
+    PermissionDenied = 4,
+    Unsupported = 5,
+    DatabaseError = 6,
+    InvalidArgument = 7,
+    InternalError = 8,
+}
+
+pub struct RustteryBattery(BatteryInfo);
+
+pub struct RustteryBatteryList(Vec<RustteryBattery>);
+
+pub struct RustteryDatabase(Database);
+
+pub struct RustteryHistory(Vec<HistoryRecord>);
+
+#[repr(C)]
+pub struct RustteryHistoryRecord {
+    pub timestamp: i64,
+    pub percent: i32,
+    pub health: i32,
+    pub percent_exact: f64,
+    pub power_now: f64,
+    pub time_to_empty: i32,
+    pub time_to_full: i32,
+}
+
+#[repr(C)]
+pub struct RustteryStatistics {
+    pub avg_percent: f64,
+    pub min_percent: i32,
+    pub max_percent: i32,
+    pub avg_power: f64,
+    pub total_records: i64,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn set_error(status: RustteryStatus, message: String) -> RustteryStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+    status
+}
+
+fn battery_error(error: BatteryError) -> RustteryStatus {
+    let status = match error.code() {
+        "no_battery" => RustteryStatus::NoBattery,
+        "unknown_battery" => RustteryStatus::UnknownBattery,
+        "permission_denied" => RustteryStatus::PermissionDenied,
+        "unsupported_platform" => RustteryStatus::Unsupported,
+        _ => RustteryStatus::ReadFailed,
+    };
+    set_error(status, error.to_string())
+}
+
+fn database_error(error: rusqlite::Error) -> RustteryStatus {
+    set_error(RustteryStatus::DatabaseError, error.to_string())
+}
+
+// Ningún pánico puede cruzar la frontera con C.
+fn guard(body: impl FnOnce() -> RustteryStatus) -> RustteryStatus {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    catch_unwind(AssertUnwindSafe(body)).unwrap_or_else(|_| {
+        set_error(
+            RustteryStatus::InternalError,
+            messages().internal_error.to_string(),
+        )
+    })
+}
+
+unsafe fn optional_str<'a>(value: *const c_char) -> Result<Option<&'a str>, RustteryStatus> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(value).to_str().map(Some).map_err(|_| {
+        set_error(
+            RustteryStatus::InvalidArgument,
+            messages().invalid_utf8.to_string(),
+        )
+    })
+}
+
+fn owned_string(value: Option<&str>) -> *mut c_char {
+    value
+        .and_then(|v| CString::new(v).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn json_string(value: &impl serde::Serialize) -> *mut c_char {
+    serde_json::to_string(value)
+        .ok()
+        .and_then(|json| CString::new(json).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn missing_output() -> RustteryStatus {
+    set_error(
+        RustteryStatus::InvalidArgument,
+        messages().null_output.to_string(),
+    )
+}
+
+/// Mensaje del último error en este hilo, o NULL si la última llamada tuvo
+/// éxito. Sale en el idioma de la locale (español o inglés) y, si no es
+/// ninguno de los dos, en inglés.
+#[no_mangle]
+pub extern "C" fn rusttery_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| owned_string(last.borrow().as_deref()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Lee la batería `name`, o la vista combinada si `name` es NULL.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_read(
+    name: *const c_char,
+    out: *mut *mut RustteryBattery,
+) -> RustteryStatus {
+    guard(|| {
+        if out.is_null() {
+            return missing_output();
+        }
+        let name = match optional_str(name) {
+            Ok(name) => name,
+            Err(status) => return status,
+        };
+
+        let result = default_source()
+            .and_then(|source| source.batteries())
+            .and_then(|batteries| match name {
+                Some(name) => batteries
+                    .into_iter()
+                    .find(|b| b.name == name)
+                    .ok_or_else(|| BatteryError::UnknownBattery(name.to_string())),
+                None => combine_batteries(&batteries).ok_or(BatteryError::NoBattery),
+            });
+
+        match result {
+            Ok(info) => {
+                *out = Box::into_raw(Box::new(RustteryBattery(info)));
+                RustteryStatus::Ok
+            }
+            Err(e) => battery_error(e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_free(battery: *mut RustteryBattery) {
+    if !battery.is_null() {
+        drop(Box::from_raw(battery));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_list(
+    out: *mut *mut RustteryBatteryList,
+) -> RustteryStatus {
+    guard(|| {
+        if out.is_null() {
+            return missing_output();
+        }
+
+        match default_source().and_then(|source| source.batteries()) {
+            Ok(batteries) => {
+                let batteries = batteries.into_iter().map(RustteryBattery).collect();
+                *out = Box::into_raw(Box::new(RustteryBatteryList(batteries)));
+                RustteryStatus::Ok
+            }
+            Err(e) => battery_error(e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_list_len(list: *const RustteryBatteryList) -> usize {
+    list.as_ref().map_or(0, |list| list.0.len())
+}
+
+/// La batería pertenece a la lista y es válida hasta liberarla; no debe
+/// pasarse a `rusttery_battery_free`.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_list_get(
+    list: *const RustteryBatteryList,
+    index: usize,
+) -> *const RustteryBattery {
+    match list.as_ref().and_then(|list| list.0.get(index)) {
+        Some(battery) => battery,
+        None => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_list_free(list: *mut RustteryBatteryList) {
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+unsafe fn with_battery<T>(
+    battery: *const RustteryBattery,
+    missing: T,
+    f: impl FnOnce(&BatteryInfo) -> T,
+) -> T {
+    battery.as_ref().map_or(missing, |battery| f(&battery.0))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_name(battery: *const RustteryBattery) -> *mut c_char {
+    with_battery(battery, ptr::null_mut(), |info| {
+        owned_string(Some(&info.name))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_status(battery: *const RustteryBattery) -> *mut c_char {
+    with_battery(battery, ptr::null_mut(), |info| {
+        owned_string(info.status.as_deref())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_percent(battery: *const RustteryBattery) -> i32 {
+    with_battery(battery, -1, |info| info.percent)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_percent_exact(battery: *const RustteryBattery) -> f64 {
+    with_battery(battery, f64::NAN, |info| {
+        info.percent_exact.map_or(f64::NAN, f64::from)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_health(battery: *const RustteryBattery) -> i32 {
+    with_battery(battery, -1, |info| info.health.unwrap_or(-1))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_power_now(battery: *const RustteryBattery) -> f64 {
+    with_battery(battery, f64::NAN, |info| {
+        info.power_now.map_or(f64::NAN, f64::from)
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_time_to_empty(battery: *const RustteryBattery) -> i32 {
+    with_battery(battery, -1, |info| info.time_to_empty.unwrap_or(-1))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_time_to_full(battery: *const RustteryBattery) -> i32 {
+    with_battery(battery, -1, |info| info.time_to_full.unwrap_or(-1))
+}
+
+/// Todos los campos de la batería como objeto JSON.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_battery_json(battery: *const RustteryBattery) -> *mut c_char {
+    with_battery(battery, ptr::null_mut(), json_string)
+}
+
+/// Abre la base de datos en `path`, o en la ubicación por defecto si es
+/// NULL. Con `retention_days` mayor que 0 se borran los registros más
+/// antiguos de ese número de días; con 0 se conserva todo el historial.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_database_open(
+    path: *const c_char,
+    retention_days: u32,
+    out: *mut *mut RustteryDatabase,
+) -> RustteryStatus {
+    guard(|| {
+        if out.is_null() {
+            return missing_output();
+        }
+        let path = match optional_str(path) {
+            Ok(path) => path,
+            Err(status) => return status,
+        };
+
+        let path = path.map_or_else(Database::default_path, PathBuf::from);
+        let result = Database::open_with_retention(path, retention_days);
+
+        match result {
+            Ok(db) => {
+                *out = Box::into_raw(Box::new(RustteryDatabase(db)));
+                RustteryStatus::Ok
+            }
+            Err(e) => database_error(e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_database_free(db: *mut RustteryDatabase) {
+    if !db.is_null() {
+        drop(Box::from_raw(db));
+    }
+}
+
+/// Historial de las últimas `hours` horas, de una batería o de todas si
+/// `battery` es NULL.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_database_history(
+    db: *const RustteryDatabase,
+    hours: i64,
+    battery: *const c_char,
+    out: *mut *mut RustteryHistory,
+) -> RustteryStatus {
+    guard(|| {
+        let Some(db) = db.as_ref() else {
+            return set_error(
+                RustteryStatus::InvalidArgument,
+                messages().null_database.to_string(),
+            );
+        };
+        if out.is_null() {
+            return missing_output();
+        }
+        let battery = match optional_str(battery) {
+            Ok(battery) => battery,
+            Err(status) => return status,
+        };
+
+        match db.0.get_history(hours, battery) {
+            Ok(records) => {
+                *out = Box::into_raw(Box::new(RustteryHistory(records)));
+                RustteryStatus::Ok
+            }
+            Err(e) => database_error(e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_database_statistics(
+    db: *const RustteryDatabase,
+    hours: i64,
+    battery: *const c_char,
+    out: *mut RustteryStatistics,
+) -> RustteryStatus {
+    guard(|| {
+        let Some(db) = db.as_ref() else {
+            return set_error(
+                RustteryStatus::InvalidArgument,
+                messages().null_database.to_string(),
+            );
+        };
+        if out.is_null() {
+            return missing_output();
+        }
+        let battery = match optional_str(battery) {
+            Ok(battery) => battery,
+            Err(status) => return status,
+        };
+
+        match db.0.get_statistics(hours, battery) {
+            Ok(stats) => {
+                *out = RustteryStatistics {
+                    avg_percent: stats.avg_percent,
+                    min_percent: stats.min_percent,
+                    max_percent: stats.max_percent,
+                    avg_power: stats.avg_power,
+                    total_records: stats.total_records,
+                };
+                RustteryStatus::Ok
+            }
+            Err(e) => database_error(e),
+        }
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_history_len(history: *const RustteryHistory) -> usize {
+    history.as_ref().map_or(0, |history| history.0.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_history_get(
+    history: *const RustteryHistory,
+    index: usize,
+    out: *mut RustteryHistoryRecord,
+) -> RustteryStatus {
+    guard(|| {
+        if out.is_null() {
+            return missing_output();
+        }
+        let Some(record) = history.as_ref().and_then(|history| history.0.get(index)) else {
+            return set_error(
+                RustteryStatus::InvalidArgument,
+                (messages().history_index_out_of_range)(index),
+            );
+        };
+
+        *out = RustteryHistoryRecord {
+            timestamp: record.timestamp,
+            percent: record.percent,
+            health: record.health.unwrap_or(-1),
+            percent_exact: record.percent_exact.map_or(f64::NAN, f64::from),
+            power_now: record.power_now.map_or(f64::NAN, f64::from),
+            time_to_empty: record.time_to_empty.unwrap_or(-1),
+            time_to_full: record.time_to_full.unwrap_or(-1),
+        };
+        RustteryStatus::Ok
+    })
+}
+
+/// Nombre de la batería del registro, o NULL si no consta.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_history_battery(
+    history: *const RustteryHistory,
+    index: usize,
+) -> *mut c_char {
+    let record = history.as_ref().and_then(|history| history.0.get(index));
+    owned_string(record.and_then(|r| r.battery.as_deref()))
+}
+
+/// El historial completo como array JSON.
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_history_json(history: *const RustteryHistory) -> *mut c_char {
+    history
+        .as_ref()
+        .map_or(ptr::null_mut(), |history| json_string(&history.0))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn rusttery_history_free(history: *mut RustteryHistory) {
+    if !history.is_null() {
+        drop(Box::from_raw(history));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // La cabecera que se distribuye debe coincidir con la que genera
+    // build.rs a partir de este módulo.
+    #[test]
+    fn header_is_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/rusttery.h"));
+        let published = include_str!("../../include/rusttery.h");
+        assert!(
+            generated == published,
+            "include/rusttery.h está desactualizada; cópiala de {}/rusttery.h",
+            env!("OUT_DIR")
+        );
+    }
+}
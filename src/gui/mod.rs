@@ -1,22 +1,65 @@
 use eframe::egui;
-use std::time::Duration;
-use crate::core::{get_battery_info, BatteryInfo};
+use std::thread;
+use crate::core::{
+    BatteryError, BatteryInfo, BatteryMonitor, CapacityUnit, EstimateMethod, DeviceBatteryInfo,
+    PowerSourceInfo, PowerSourceKind, ReadMethod,
+};
+use crate::i18n::messages;
 
 pub struct BatteryApp {
+    monitor: BatteryMonitor,
+    batteries: Vec<BatteryInfo>,
     battery_info: Option<BatteryInfo>,
+    selected: Option<String>,
+    error: Option<String>,
+    power_sources: Vec<PowerSourceInfo>,
+    devices: Vec<DeviceBatteryInfo>,
 }
 
-impl Default for BatteryApp {
-    fn default() -> Self {
-        Self {
-            battery_info: get_battery_info(),
+impl BatteryApp {
+    pub fn new(monitor: BatteryMonitor) -> Self {
+        let mut app = Self {
+            monitor,
+            batteries: Vec::new(),
+            battery_info: None,
+            selected: None,
+            error: None,
+            power_sources: Vec::new(),
+            devices: Vec::new(),
+        };
+        app.refresh();
+        app
+    }
+
+    fn refresh(&mut self) {
+        let snapshot = self.monitor.latest();
+        self.power_sources = snapshot.power_sources.clone();
+        self.devices = snapshot.devices.clone();
+        self.batteries = snapshot.batteries.clone();
+
+        self.error = match &snapshot.error {
+            Some(e) => Some(e.to_string()),
+            None if snapshot.batteries.is_empty() => Some(BatteryError::NoBattery.to_string()),
+            None => None,
+        };
+
+        if let Some(name) = &self.selected {
+            if !self.batteries.iter().any(|b| &b.name == name) {
+                self.selected = None;
+            }
         }
+
+        self.battery_info = match &self.selected {
+            Some(name) => self.batteries.iter().find(|b| &b.name == name).cloned(),
+            None => snapshot.combined.clone(),
+        };
     }
 }
 
 impl eframe::App for BatteryApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.battery_info = get_battery_info();
+        self.refresh();
+        let t = messages();
 
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -26,6 +69,25 @@ impl eframe::App for BatteryApp {
                     ui.heading("🔋 Rusttery");
                     ui.add_space(20.0);
 
+                    if self.batteries.len() > 1 {
+                        let mut selected = self.selected.clone();
+                        ui.horizontal(|ui| {
+                            ui.selectable_value(&mut selected, None, t.total);
+                            for battery in &self.batteries {
+                                ui.selectable_value(
+                                    &mut selected,
+                                    Some(battery.name.clone()),
+                                    &battery.name,
+                                );
+                            }
+                        });
+                        if selected != self.selected {
+                            self.selected = selected;
+                            self.refresh();
+                        }
+                        ui.add_space(10.0);
+                    }
+
                     if let Some(info) = &self.battery_info {
                         ui.label(
                             egui::RichText::new(format!("{}%", info.percent))
@@ -35,7 +97,7 @@ impl eframe::App for BatteryApp {
                         
                         ui.add_space(5.0);
                         ui.label(
-                            egui::RichText::new("Carga actual")
+                            egui::RichText::new(t.current_charge)
                                 .size(18.0)
                         );
 
@@ -43,7 +105,7 @@ impl eframe::App for BatteryApp {
 
                         if let Some(health) = info.health {
                             ui.label(
-                                egui::RichText::new(format!("Salud: {}%", health))
+                                egui::RichText::new(format!("{}: {}%", t.health, health))
                                     .size(28.0)
                                     .color(egui::Color32::from_rgb(100, 150, 255))
                             );
@@ -53,7 +115,7 @@ impl eframe::App for BatteryApp {
                         ui.separator();
                         ui.add_space(10.0);
 
-                        ui.label(egui::RichText::new("Detalles").size(20.0).strong());
+                        ui.label(egui::RichText::new(t.details).size(20.0).strong());
                         ui.add_space(10.0);
 
                         egui::Grid::new("battery_grid")
@@ -61,96 +123,265 @@ impl eframe::App for BatteryApp {
                             .spacing([20.0, 8.0])
                             .striped(true)
                             .show(ui, |ui| {
+                                ui.label(format!("{}:", t.battery));
+                                ui.label(&info.name);
+                                ui.end_row();
+
                                 if let Some(status) = &info.status {
-                                    ui.label("Estado:");
+                                    ui.label(format!("{}:", t.status));
                                     ui.label(status);
                                     ui.end_row();
                                 }
 
                                 if let Some(cycles) = info.cycle_count {
-                                    ui.label("Ciclos:");
+                                    ui.label(format!("{}:", t.cycles));
                                     ui.label(format!("{}", cycles));
                                     ui.end_row();
                                 }
 
                                 if let Some(voltage) = info.voltage_now {
-                                    ui.label("Voltaje:");
+                                    ui.label(format!("{}:", t.voltage));
                                     ui.label(format!("{:.2} V", voltage));
                                     ui.end_row();
                                 }
 
                                 if let Some(current) = info.current_now {
-                                    ui.label("Corriente:");
+                                    ui.label(format!("{}:", t.current));
                                     ui.label(format!("{:.2} A", current));
                                     ui.end_row();
                                 }
 
                                 if let Some(power) = info.power_now {
-                                    ui.label("Potencia:");
+                                    ui.label(format!("{}:", t.power));
                                     ui.label(format!("{:.2} W", power));
                                     ui.end_row();
                                 }
 
                                 if let Some(tech) = &info.technology {
-                                    ui.label("Tecnología:");
+                                    ui.label(format!("{}:", t.technology));
                                     ui.label(tech);
                                     ui.end_row();
                                 }
 
                                 if let Some(manufacturer) = &info.manufacturer {
-                                    ui.label("Fabricante:");
+                                    ui.label(format!("{}:", t.manufacturer));
                                     ui.label(manufacturer);
                                     ui.end_row();
                                 }
 
                                 if let Some(model) = &info.model {
-                                    ui.label("Modelo:");
+                                    ui.label(format!("{}:", t.model));
                                     ui.label(model);
                                     ui.end_row();
                                 }
 
-                                if let Some(cap_full) = info.capacity_full {
-                                    ui.label("Capacidad actual:");
-                                    ui.label(format!("{} mWh", cap_full));
+                                if let Some(exact) = info.percent_exact {
+                                    ui.label(format!("{}:", t.percent_exact));
+                                    ui.label(format!("{:.2} %", exact));
                                     ui.end_row();
                                 }
 
-                                if let Some(cap_design) = info.capacity_design {
-                                    ui.label("Capacidad diseño:");
-                                    ui.label(format!("{} mWh", cap_design));
+                                if let Some(energy_now) = info.energy_now_wh {
+                                    ui.label(format!("{}:", t.energy_now));
+                                    ui.label(format!("{:.2} Wh", energy_now));
                                     ui.end_row();
                                 }
 
+                                if let Some(cap_full) = info.energy_full_wh {
+                                    ui.label(format!("{}:", t.energy_full));
+                                    ui.label(format!("{:.2} Wh", cap_full));
+                                    ui.end_row();
+                                }
+
+                                if let Some(cap_design) = info.energy_full_design_wh {
+                                    ui.label(format!("{}:", t.energy_full_design));
+                                    ui.label(format!("{:.2} Wh", cap_design));
+                                    ui.end_row();
+                                }
+
+                                if info.capacity_unit == Some(CapacityUnit::Charge) {
+                                    if let Some(charge_full) = info.charge_full_ah {
+                                        ui.label(format!("{}:", t.charge_full));
+                                        ui.label(format!("{:.3} Ah", charge_full));
+                                        ui.end_row();
+                                    }
+
+                                    if let Some(charge_design) = info.charge_full_design_ah {
+                                        ui.label(format!("{}:", t.charge_full_design));
+                                        ui.label(format!("{:.3} Ah", charge_design));
+                                        ui.end_row();
+                                    }
+                                }
+
                                 if let Some(time) = info.time_to_empty {
-                                    ui.label("Tiempo restante:");
+                                    ui.label(format!("{}:", t.time_to_empty));
                                     ui.label(format!("{} min", time));
                                     ui.end_row();
                                 }
 
                                 if let Some(time) = info.time_to_full {
-                                    ui.label("Tiempo hasta carga:");
+                                    ui.label(format!("{}:", t.time_to_full));
                                     ui.label(format!("{} min", time));
                                     ui.end_row();
                                 }
+
+                                if let Some(method) = info.time_method {
+                                    ui.label(format!("{}:", t.estimate));
+                                    ui.label(match method {
+                                        EstimateMethod::Kernel => t.estimate_kernel,
+                                        EstimateMethod::Power => t.estimate_power,
+                                        EstimateMethod::Current => t.estimate_current,
+                                        EstimateMethod::Smoothed => t.estimate_smoothed,
+                                    });
+                                    ui.end_row();
+                                }
+
+                                if let Some(temperature) = info.temperature {
+                                    ui.label(format!("{}:", t.temperature));
+                                    ui.label(format!("{:.1} °C", temperature));
+                                    ui.end_row();
+                                }
+
+                                if let Some(health_status) = &info.health_status {
+                                    ui.label(format!("{}:", t.health_status));
+                                    ui.label(health_status);
+                                    ui.end_row();
+                                }
+
+                                if let Some(level) = &info.capacity_level {
+                                    ui.label(format!("{}:", t.capacity_level));
+                                    ui.label(level);
+                                    ui.end_row();
+                                }
+
+                                if let Some(alarm) = info.alarm {
+                                    ui.label(format!("{}:", t.alarm));
+                                    ui.label(match info.capacity_unit {
+                                        Some(CapacityUnit::Charge) => format!("{:.3} Ah", alarm),
+                                        _ => format!("{:.2} Wh", alarm),
+                                    });
+                                    ui.end_row();
+                                }
+
+                                if let Some(voltage_min) = info.voltage_min_design {
+                                    ui.label(format!("{}:", t.voltage_min_design));
+                                    ui.label(format!("{:.2} V", voltage_min));
+                                    ui.end_row();
+                                }
+
+                                if let Some(present) = info.present {
+                                    ui.label(format!("{}:", t.present));
+                                    ui.label(if present { t.yes } else { t.no });
+                                    ui.end_row();
+                                }
+
+                                if let Some(method) = info.read_method {
+                                    ui.label(format!("{}:", t.read_method));
+                                    ui.label(match method {
+                                        ReadMethod::Uevent => "uevent",
+                                        ReadMethod::Files => t.read_method_files,
+                                    });
+                                    ui.end_row();
+                                }
                             });
 
                         ui.add_space(10.0);
                     } else {
                         ui.label(
-                            egui::RichText::new("❌ No se pudo obtener información de la batería")
+                            egui::RichText::new(format!("❌ {}", t.read_error))
                                 .size(18.0)
                                 .color(egui::Color32::RED)
                         );
+
+                        if let Some(error) = &self.error {
+                            ui.add_space(5.0);
+                            ui.label(egui::RichText::new(error).size(14.0));
+                        }
+                    }
+
+                    if !self.power_sources.is_empty() {
+                        ui.add_space(15.0);
+                        ui.separator();
+                        ui.add_space(10.0);
+
+                        ui.label(egui::RichText::new(t.power_supply).size(20.0).strong());
+                        ui.add_space(10.0);
+
+                        egui::Grid::new("power_grid")
+                            .num_columns(2)
+                            .spacing([20.0, 8.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for power in &self.power_sources {
+                                    let kind = match power.kind {
+                                        PowerSourceKind::Mains => t.mains,
+                                        PowerSourceKind::Usb => "USB",
+                                        PowerSourceKind::UsbPd => "USB PD",
+                                    };
+                                    let state = if power.online { t.connected } else { t.disconnected };
+                                    ui.label(format!("{} ({}):", power.name, kind));
+                                    ui.label(state);
+                                    ui.end_row();
+
+                                    if let Some(usb_type) = &power.usb_type {
+                                        ui.label(format!("    {}:", t.usb_type));
+                                        ui.label(usb_type);
+                                        ui.end_row();
+                                    }
+
+                                    if let Some(voltage) = power.voltage_now {
+                                        ui.label(format!("    {}:", t.voltage));
+                                        ui.label(format!("{:.2} V", voltage));
+                                        ui.end_row();
+                                    }
+
+                                    if let Some(current) = power.current_max {
+                                        ui.label(format!("    {}:", t.current_max));
+                                        ui.label(format!("{:.2} A", current));
+                                        ui.end_row();
+                                    }
+
+                                    if let Some(watts) = power.power_max() {
+                                        ui.label(format!("    {}:", t.power_max));
+                                        ui.label(format!("{:.1} W", watts));
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+                    }
+
+                    if !self.devices.is_empty() {
+                        ui.add_space(15.0);
+                        ui.separator();
+                        ui.add_space(10.0);
+
+                        ui.label(egui::RichText::new(t.peripherals).size(20.0).strong());
+                        ui.add_space(10.0);
+
+                        egui::Grid::new("device_grid")
+                            .num_columns(2)
+                            .spacing([20.0, 8.0])
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for device in &self.devices {
+                                    ui.label(format!("{}:", device.display_name()));
+                                    let level = match (device.percent, &device.capacity_level) {
+                                        (Some(percent), _) => format!("{}%", percent),
+                                        (None, Some(level)) => level.clone(),
+                                        (None, None) => "-".to_string(),
+                                    };
+                                    ui.label(level);
+                                    ui.end_row();
+                                }
+                            });
                     }
                 });
             });
         });
-
-        ctx.request_repaint_after(Duration::from_secs(3));
     }
 }
 
-pub fn run() -> Result<(), eframe::Error> {
+pub fn run(monitor: BatteryMonitor) -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([500.0, 600.0])
@@ -161,6 +392,16 @@ pub fn run() -> Result<(), eframe::Error> {
     eframe::run_native(
         "Rusttery",
         options,
-        Box::new(|_cc| Ok(Box::new(BatteryApp::default()))),
+        Box::new(|cc| {
+            // Cada nueva lectura del monitor repinta la ventana.
+            let ctx = cc.egui_ctx.clone();
+            let snapshots = monitor.subscribe();
+            thread::spawn(move || {
+                for _ in snapshots {
+                    ctx.request_repaint();
+                }
+            });
+            Ok(Box::new(BatteryApp::new(monitor)))
+        }),
     )
 }
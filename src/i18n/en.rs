@@ -0,0 +1,259 @@
+use super::Messages;
+
+pub(super) static MESSAGES: Messages = Messages {
+    no_battery: "No battery found",
+    unknown_battery: |name| format!("There is no battery named '{}'", name),
+    access_failed: |path, e| format!("Could not access {}: {}", path.display(), e),
+    read_failed: |path, e| format!("Error reading {}: {}", path.display(), e),
+    write_failed: |path, e| format!("Error writing {}: {}", path.display(), e),
+    invalid_value: |place, value| format!("Invalid value in {}: '{}'", place, value),
+    call_failed: |call, message| format!("Error in {}: {}", call, message),
+    zero_capacity: |name| format!("Battery '{}' reports a full capacity of 0", name),
+    unsupported_platform: "Unsupported operating system",
+    supported_platforms: "Rusttery only works on Windows and Linux.",
+    thresholds_unsupported: |battery, attribute| {
+        format!(
+            "Battery '{}' does not support charge thresholds ({} is missing)",
+            battery, attribute
+        )
+    },
+    hotplug_unavailable: |e| format!("Cannot receive connection events: {}", e),
+    no_threshold: "No threshold was given",
+    threshold_out_of_range: |value| format!("Threshold {} is outside the 0-100 range", value),
+    threshold_order: |start, end| {
+        format!(
+            "The start threshold ({}) must be lower than the end threshold ({})",
+            start, end
+        )
+    },
+    behaviour_unavailable: |mode, available| {
+        format!(
+            "Charge mode '{}' is not available (available: {})",
+            mode, available
+        )
+    },
+    read_error: "Could not read battery information",
+    monitor_thread_failed: "Could not start the monitoring thread",
+
+    time: "Time",
+    name: "Name",
+    battery: "Battery",
+    total: "Total",
+    charge: "Charge",
+    current_charge: "Current charge",
+    percent_exact: "Exact charge",
+    health: "Health",
+    status: "Status",
+    cycles: "Cycles",
+    voltage: "Voltage",
+    current: "Current",
+    power: "Power",
+    technology: "Technology",
+    manufacturer: "Manufacturer",
+    model: "Model",
+    serial_number: "Serial number",
+    capacity_unit: "Unit",
+    energy_now: "Remaining energy",
+    energy_full: "Current capacity",
+    energy_full_design: "Design capacity",
+    charge_now: "Remaining charge",
+    charge_full: "Full charge",
+    charge_full_design: "Design charge",
+    voltage_min_design: "Minimum design voltage",
+    time_to_empty: "Time remaining",
+    time_to_full: "Time to full",
+    time_to_empty_short: "Remaining",
+    time_to_full_short: "To full",
+    estimate: "Estimate",
+    estimate_kernel: "System",
+    estimate_power: "Energy / power",
+    estimate_current: "Charge / current",
+    estimate_smoothed: "Moving average",
+    temperature: "Temperature",
+    capacity_level: "Capacity level",
+    alarm: "Alarm",
+    present: "Present",
+    health_status: "Health status",
+    read_method: "sysfs reading",
+    read_method_files: "One file per attribute",
+    records: "Records",
+    avg_percent: "Average charge",
+    min_percent: "Minimum charge",
+    max_percent: "Maximum charge",
+    avg_power: "Average power",
+    yes: "Yes",
+    no: "No",
+    unknown_status: "unknown",
+
+    power_supply: "Power supply",
+    on_battery: "battery",
+    mains: "mains",
+    connected: "connected",
+    disconnected: "disconnected",
+    usb_type: "Negotiated type",
+    current_max: "Max. current",
+    power_max: "Max. power",
+    peripherals: "Peripherals",
+    device: "Device",
+    level: "Level",
+
+    event: "Event",
+    charger_connected: |name| format!("Charger connected ({})", name),
+    charger_disconnected: |name| format!("Charger disconnected ({})", name),
+    battery_added: |name| format!("Battery inserted ({})", name),
+    battery_removed: |name| format!("Battery removed ({})", name),
+    status_changed: |name, status| format!("{}: status {}", name, status),
+    warning: "Warning",
+    threshold_crossed: |name, threshold, rising| {
+        let direction = if rising {
+            "risen above"
+        } else {
+            "dropped below"
+        };
+        format!("{} has {} {}%", name, direction, threshold)
+    },
+
+    details: "Details",
+    current_reading: "Current reading",
+    history_title: |range| format!("History ({})", range),
+    days: |days| format!("{} days", days),
+    database_unavailable: "Database not available",
+    no_records_hours: |hours| format!("No records in the last {} hours", hours),
+    daemon_hint: "Readings are stored by `rusttery daemon`",
+    max_short: "max.",
+    no_data: "no data",
+    recent_changes: "Recent changes",
+    no_changes: "No changes",
+    tui_help: "Tab/←→: battery · 1-4/r: statistics range · q: quit",
+    gui_failed: "Error starting the GUI",
+    tui_failed: "Error starting the terminal interface",
+
+    server_running: |host, port| format!("Rusttery server running at http://{}:{}", host, port),
+    server_failed: |bind, port| format!("Could not start the server on {}:{}", bind, port),
+    database_failed: "Could not open the database",
+    endpoints: "Available endpoints:",
+    endpoint_descriptions: [
+        "Current charge and health",
+        "Health only",
+        "Current charge only",
+        "Full information",
+        "All batteries and combined view",
+        "Chargers and power supplies",
+        "Peripheral batteries",
+        "Charge history",
+        "Statistics",
+        "Charger and battery connections",
+        "Charge thresholds",
+        "Change thresholds (requires RUSTTERY_API_TOKEN)",
+    ],
+    battery_param_hint: "(?battery=BAT0 selects a specific battery)",
+    route_not_found: "Route not found",
+    method_not_allowed: "Method not allowed",
+    body_unreadable: "Could not read the request body",
+    invalid_json: "Invalid JSON: expected {\"start\", \"end\", \"behaviour\"}",
+    writes_disabled: "Writes disabled: set RUSTTERY_API_TOKEN to enable them",
+    invalid_token: "Invalid authorization token",
+    history_failed: "Error fetching history",
+    statistics_failed: "Error fetching statistics",
+    events_failed: "Error fetching events",
+
+    error: "Error",
+    serialize_failed: "Error serializing data",
+    unknown_field: |key, known| format!("Unknown field: {}. Available fields: {}", key, known),
+    fields_required: "--fields needs at least one field",
+    charge_start: "Charge start",
+    charge_end: "Charge stop",
+    charge_mode: "Mode",
+    threshold_permission: "Administrator permissions are needed to change the thresholds (sudo).",
+    until_before_since: |until, since| {
+        format!("--until ({}) is before the start ({})", until, since)
+    },
+    no_records: |battery, since, until| {
+        format!(
+            "No records{} between {} and {}",
+            battery.map(|b| format!(" for {}", b)).unwrap_or_default(),
+            since,
+            until.unwrap_or("now")
+        )
+    },
+    database_open_failed: |path, e| {
+        format!("Could not open the database {}: {}", path.display(), e)
+    },
+    create_failed: |path, e| format!("Could not create {}: {}", path.display(), e),
+    exported: |count, path| format!("{} records exported to {}", count, path.display()),
+    export_failed: "Error exporting the history",
+    template_unclosed: |template| format!("Missing '}}' in the template: {}", template),
+    template_unopened: |template| format!("Unopened '}}' in the template: {}", template),
+    template_unknown_field: |key, known| {
+        format!(
+            "Unknown field in the template: {}. Available fields: {}",
+            key, known
+        )
+    },
+    template_invalid_format: |spec, key| {
+        format!(
+            "Invalid format in {{{}}}: expected :.N (for example {{{}:.1}})",
+            spec, key
+        )
+    },
+    invalid_time: "expected a duration (30m, 12h, 2d, 1w) or a date (2026-10-01 08:00)",
+    invalid_language: "expected es or en",
+
+    // La ayuda y los nombres de los valores se escriben en inglés en args.rs.
+    help: &[],
+    value_names: &[],
+
+    check_health: |health| format!("health {}%", health),
+    check_discharge: |rate| format!("discharge {:.1} %/h", rate),
+    check_percent_below: |limit| format!("charge below {}%", limit),
+    check_health_below: |limit| format!("health below {}%", limit),
+    check_discharge_above: |limit| format!("discharge above {} %/h", limit),
+    check_no_health: "the battery does not report its health",
+    check_no_history: "not enough history for the discharge rate",
+
+    source_default: "default value",
+    source_file: "configuration file",
+    source_env: |var| format!("variable {}", var),
+    source_flag: |flag| format!("option {}", flag),
+    config_file: |path, found| {
+        format!(
+            "File: {}{}",
+            path.display(),
+            if found { "" } else { " (does not exist)" }
+        )
+    },
+    config_error: |path, e| format!("Error in {}: {}", path.display(), e),
+    config_invalid: |key, message| format!("Invalid configuration ({}): {}", key, message),
+    interval_too_short: "the interval must be at least 1 second",
+
+    signal_handler_failed: |e| format!("Could not install the signal handler: {}", e),
+    watchdog_interval: |interval, watchdog| {
+        format!(
+            "Warning: the interval ({} s) is more than half the systemd watchdog ({} s)",
+            interval, watchdog
+        )
+    },
+    daemon_started: |interval, path, dedup| {
+        format!(
+            "Rusttery recording every {} s to {} (dedup: {})",
+            interval,
+            path.display(),
+            dedup
+        )
+    },
+    monitor_stopped: "The battery monitor has stopped",
+    daemon_stopped: |saved| format!("Rusttery stopped ({} readings saved)", saved),
+    save_event_failed: |e| format!("Error saving the event: {}", e),
+    save_reading_failed: |e| format!("Error saving the reading: {}", e),
+    battery_unreadable: |e| format!("Could not read the battery: {}", e),
+    daemon_status: |name, percent, saved| {
+        format!("{}: {}% | {} readings saved", name, percent, saved)
+    },
+    daemon_status_no_battery: |saved| format!("No battery | {} readings saved", saved),
+
+    internal_error: "Internal error in Rusttery",
+    invalid_utf8: "The string is not valid UTF-8",
+    null_output: "The output pointer is null",
+    null_database: "Null database",
+    history_index_out_of_range: |index| format!("Index {} is outside the history", index),
+};
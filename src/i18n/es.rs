@@ -0,0 +1,469 @@
+use super::Messages;
+
+pub(super) static MESSAGES: Messages = Messages {
+    no_battery: "No se encontró ninguna batería",
+    unknown_battery: |name| format!("No existe la batería '{}'", name),
+    access_failed: |path, e| format!("No se pudo acceder a {}: {}", path.display(), e),
+    read_failed: |path, e| format!("Error al leer {}: {}", path.display(), e),
+    write_failed: |path, e| format!("Error al escribir {}: {}", path.display(), e),
+    invalid_value: |place, value| format!("Valor no válido en {}: '{}'", place, value),
+    call_failed: |call, message| format!("Error en {}: {}", call, message),
+    zero_capacity: |name| format!("La batería '{}' informa una capacidad completa de 0", name),
+    unsupported_platform: "Sistema operativo no soportado",
+    supported_platforms: "Rusttery solo funciona en Windows y Linux.",
+    thresholds_unsupported: |battery, attribute| {
+        format!(
+            "La batería '{}' no permite configurar umbrales de carga (falta {})",
+            battery, attribute
+        )
+    },
+    hotplug_unavailable: |e| format!("No se pueden recibir eventos de conexión: {}", e),
+    no_threshold: "No se indicó ningún umbral",
+    threshold_out_of_range: |value| format!("El umbral {} está fuera del rango 0-100", value),
+    threshold_order: |start, end| {
+        format!(
+            "El umbral de inicio ({}) debe ser menor que el de fin ({})",
+            start, end
+        )
+    },
+    behaviour_unavailable: |mode, available| {
+        format!(
+            "Modo de carga '{}' no disponible (disponibles: {})",
+            mode, available
+        )
+    },
+    read_error: "No se pudo obtener información de la batería",
+    monitor_thread_failed: "No se pudo iniciar el hilo de monitorización",
+
+    time: "Hora",
+    name: "Nombre",
+    battery: "Batería",
+    total: "Total",
+    charge: "Carga",
+    current_charge: "Carga actual",
+    percent_exact: "Carga exacta",
+    health: "Salud",
+    status: "Estado",
+    cycles: "Ciclos",
+    voltage: "Voltaje",
+    current: "Corriente",
+    power: "Potencia",
+    technology: "Tecnología",
+    manufacturer: "Fabricante",
+    model: "Modelo",
+    serial_number: "Número de serie",
+    capacity_unit: "Unidad",
+    energy_now: "Energía restante",
+    energy_full: "Capacidad actual",
+    energy_full_design: "Capacidad diseño",
+    charge_now: "Carga restante",
+    charge_full: "Carga completa",
+    charge_full_design: "Carga diseño",
+    voltage_min_design: "Voltaje mínimo diseño",
+    time_to_empty: "Tiempo restante",
+    time_to_full: "Tiempo hasta carga",
+    time_to_empty_short: "Restante",
+    time_to_full_short: "Hasta carga",
+    estimate: "Estimación",
+    estimate_kernel: "Sistema",
+    estimate_power: "Energía / potencia",
+    estimate_current: "Carga / corriente",
+    estimate_smoothed: "Media móvil",
+    temperature: "Temperatura",
+    capacity_level: "Nivel de carga",
+    alarm: "Alarma",
+    present: "Presente",
+    health_status: "Estado de salud",
+    read_method: "Lectura de sysfs",
+    read_method_files: "Archivo por atributo",
+    records: "Registros",
+    avg_percent: "Carga media",
+    min_percent: "Carga mínima",
+    max_percent: "Carga máxima",
+    avg_power: "Potencia media",
+    yes: "Sí",
+    no: "No",
+    unknown_status: "desconocido",
+
+    power_supply: "Alimentación",
+    on_battery: "batería",
+    mains: "red eléctrica",
+    connected: "conectado",
+    disconnected: "desconectado",
+    usb_type: "Tipo negociado",
+    current_max: "Corriente máx.",
+    power_max: "Potencia máx.",
+    peripherals: "Periféricos",
+    device: "Dispositivo",
+    level: "Nivel",
+
+    event: "Evento",
+    charger_connected: |name| format!("Cargador conectado ({})", name),
+    charger_disconnected: |name| format!("Cargador desconectado ({})", name),
+    battery_added: |name| format!("Batería insertada ({})", name),
+    battery_removed: |name| format!("Batería retirada ({})", name),
+    status_changed: |name, status| format!("{}: estado {}", name, status),
+    warning: "Aviso",
+    threshold_crossed: |name, threshold, rising| {
+        let direction = if rising { "superado" } else { "bajado del" };
+        format!("{} ha {} {}%", name, direction, threshold)
+    },
+
+    details: "Detalles",
+    current_reading: "Lectura actual",
+    history_title: |range| format!("Historial ({})", range),
+    days: |days| format!("{} días", days),
+    database_unavailable: "Base de datos no disponible",
+    no_records_hours: |hours| format!("Sin registros en las últimas {} horas", hours),
+    daemon_hint: "Las lecturas se guardan con `rusttery daemon`",
+    max_short: "máx.",
+    no_data: "sin datos",
+    recent_changes: "Cambios recientes",
+    no_changes: "Sin cambios",
+    tui_help: "Tab/←→: batería · 1-4/r: intervalo de estadísticas · q: salir",
+    gui_failed: "Error al iniciar la GUI",
+    tui_failed: "Error al iniciar la interfaz de terminal",
+
+    server_running: |host, port| {
+        format!("Servidor Rusttery ejecutándose en http://{}:{}", host, port)
+    },
+    server_failed: |bind, port| format!("No se pudo iniciar el servidor en {}:{}", bind, port),
+    database_failed: "No se pudo iniciar la base de datos",
+    endpoints: "Endpoints disponibles:",
+    endpoint_descriptions: [
+        "Carga actual y salud",
+        "Solo salud",
+        "Solo carga actual",
+        "Información completa",
+        "Todas las baterías y vista combinada",
+        "Cargadores y fuentes de alimentación",
+        "Baterías de periféricos",
+        "Historial de carga",
+        "Estadísticas",
+        "Conexiones de cargadores y baterías",
+        "Umbrales de carga",
+        "Modificar umbrales (requiere RUSTTERY_API_TOKEN)",
+    ],
+    battery_param_hint: "(?battery=BAT0 selecciona una batería concreta)",
+    route_not_found: "Ruta no encontrada",
+    method_not_allowed: "Método no permitido",
+    body_unreadable: "No se pudo leer el cuerpo de la petición",
+    invalid_json: "JSON no válido: se esperaba {\"start\", \"end\", \"behaviour\"}",
+    writes_disabled: "Escritura deshabilitada: define RUSTTERY_API_TOKEN para habilitarla",
+    invalid_token: "Token de autorización no válido",
+    history_failed: "Error al obtener historial",
+    statistics_failed: "Error al obtener estadísticas",
+    events_failed: "Error al obtener eventos",
+
+    error: "Error",
+    serialize_failed: "Error al serializar datos",
+    unknown_field: |key, known| {
+        format!("Campo desconocido: {}. Campos disponibles: {}", key, known)
+    },
+    fields_required: "--fields necesita al menos un campo",
+    charge_start: "Inicio de carga",
+    charge_end: "Fin de carga",
+    charge_mode: "Modo",
+    threshold_permission:
+        "Se necesitan permisos de administrador para modificar los umbrales (sudo).",
+    until_before_since: |until, since| {
+        format!("--until ({}) es anterior al inicio ({})", until, since)
+    },
+    no_records: |battery, since, until| {
+        format!(
+            "No hay registros{} entre {} y {}",
+            battery.map(|b| format!(" de {}", b)).unwrap_or_default(),
+            since,
+            until.unwrap_or("ahora")
+        )
+    },
+    database_open_failed: |path, e| {
+        format!(
+            "No se pudo abrir la base de datos {}: {}",
+            path.display(),
+            e
+        )
+    },
+    create_failed: |path, e| format!("No se pudo crear {}: {}", path.display(), e),
+    exported: |count, path| format!("{} registros exportados a {}", count, path.display()),
+    export_failed: "Error al exportar el historial",
+    template_unclosed: |template| format!("Falta '}}' en la plantilla: {}", template),
+    template_unopened: |template| format!("'}}' sin abrir en la plantilla: {}", template),
+    template_unknown_field: |key, known| {
+        format!(
+            "Campo desconocido en la plantilla: {}. Campos disponibles: {}",
+            key, known
+        )
+    },
+    template_invalid_format: |spec, key| {
+        format!(
+            "Formato no válido en {{{}}}: se espera :.N (por ejemplo {{{}:.1}})",
+            spec, key
+        )
+    },
+    invalid_time: "se espera una duración (30m, 12h, 2d, 1w) o una fecha (2026-10-01 08:00)",
+    invalid_language: "se espera es o en",
+
+    help: &[
+        (
+            "Battery monitor for Linux and Windows. Opens the GUI without a subcommand",
+            "Monitor de batería para Linux y Windows. Sin subcomando abre la GUI.",
+        ),
+        (
+            "Configuration file (default ~/.config/rusttery/config.toml)",
+            "Archivo de configuración (por defecto ~/.config/rusttery/config.toml)",
+        ),
+        (
+            "SQLite history database (default in the user's data directory)",
+            "Base de datos SQLite del historial (por defecto en el directorio de datos del usuario)",
+        ),
+        (
+            "Days the history is kept (0 keeps it forever)",
+            "Días que se conserva el historial (0 lo conserva siempre)",
+        ),
+        (
+            "Language of the messages (default the one from LANG, or Spanish)",
+            "Idioma de los mensajes (por defecto el de LANG, o español)",
+        ),
+        ("Opens the window with the battery data", "Abre la ventana con los datos de la batería"),
+        (
+            "Opens a full-screen terminal dashboard with power and charge charts",
+            "Abre un panel a pantalla completa en la terminal con gráficas de potencia y carga",
+        ),
+        (
+            "Shows battery changes in the terminal and stores them in the history",
+            "Muestra los cambios de la batería en la terminal y los guarda en el historial",
+        ),
+        (
+            "Records readings into the history in the background (systemd service)",
+            "Registra lecturas en el historial en segundo plano (servicio de systemd)",
+        ),
+        ("Starts the REST API server", "Inicia el servidor de la API REST"),
+        ("Prints the current reading and exits", "Imprime la lectura actual y termina"),
+        (
+            "Prints the current reading as JSON and exits",
+            "Imprime la lectura actual en JSON y termina",
+        ),
+        ("Shows the charge history", "Muestra el historial de carga"),
+        ("Shows history statistics", "Muestra estadísticas del historial"),
+        ("Exports the history to CSV or JSON", "Exporta el historial a CSV o JSON"),
+        (
+            "Continuous output for status bars (waybar, i3blocks, polybar, tmux)",
+            "Salida continua para barras de estado (waybar, i3blocks, polybar, tmux)",
+        ),
+        (
+            "Nagios/Icinga check: one line with perfdata and exit code 0-3",
+            "Comprobación para Nagios/Icinga: una línea con perfdata y código de salida 0-3",
+        ),
+        ("Shows or changes the charge thresholds", "Consulta o modifica los umbrales de carga"),
+        ("Shows the current thresholds", "Muestra los umbrales actuales"),
+        (
+            "Changes the thresholds (usually requires sudo)",
+            "Modifica los umbrales (normalmente requiere sudo)",
+        ),
+        ("Shows the effective configuration", "Muestra la configuración efectiva"),
+        (
+            "Prints the merged configuration and where each value comes from",
+            "Imprime la configuración combinada y el origen de cada valor",
+        ),
+        (
+            "Generates the completion script for the given shell",
+            "Genera el script de autocompletado para la shell indicada",
+        ),
+        (
+            "Seconds between battery readings (default 3)",
+            "Segundos entre lecturas de la batería (por defecto 3)",
+        ),
+        (
+            "Only the combined view of all batteries",
+            "Solo la vista combinada de todas las baterías",
+        ),
+        (
+            "Output format (default line in watch and status, table in history and stats)",
+            "Formato de salida (por defecto line en watch y status, table en history y stats)",
+        ),
+        (
+            "Comma-separated fields (name,percent,health,status,power_now,...)",
+            "Campos separados por comas (name,percent,health,status,power_now,...)",
+        ),
+        (
+            "Readings that are stored: all (off), those that change the percentage (percent) or those that change percentage, health or status (changes)",
+            "Lecturas que se guardan: todas (off), las que cambian el porcentaje (percent) o las que cambian porcentaje, salud o estado (changes)",
+        ),
+        ("HTTP port of the server (default 3000)", "Puerto HTTP del servidor (por defecto 3000)"),
+        (
+            "Address the server listens on (default 0.0.0.0)",
+            "Dirección en la que escucha el servidor (por defecto 0.0.0.0)",
+        ),
+        ("Hours back from now (default 24)", "Horas hacia atrás desde ahora (por defecto 24)"),
+        (
+            "Since when: how long ago (30m, 12h, 2d, 1w, 1d12h) or a date (\"2026-10-01\", \"2026-10-01 08:00\")",
+            "Desde cuándo: hace cuánto (30m, 12h, 2d, 1w, 1d12h) o una fecha (\"2026-10-01\", \"2026-10-01 08:00\")",
+        ),
+        (
+            "Until when, in the same format as --since (default now)",
+            "Hasta cuándo, con el mismo formato que --since (por defecto ahora)",
+        ),
+        (
+            "Only the records of this battery (BAT0, BAT1, ...)",
+            "Solo los registros de esta batería (BAT0, BAT1, ...)",
+        ),
+        ("Format of the exported file", "Formato del archivo exportado"),
+        (
+            "Destination file (default the standard output)",
+            "Archivo de destino (por defecto la salida estándar)",
+        ),
+        ("Target bar", "Barra de destino"),
+        (
+            "Text with placeholders such as {percent}, {power:.1}, {time_to_empty} or {icon}",
+            "Texto con marcadores como {percent}, {power:.1}, {time_to_empty} o {icon}",
+        ),
+        ("Tooltip template (waybar only)", "Plantilla de la descripción emergente (solo waybar)"),
+        (
+            "Comma-separated {icon} icons, from lowest to highest charge (default the Font Awesome ones)",
+            "Iconos de {icon} separados por comas, de menos a más carga (por defecto los de Font Awesome)",
+        ),
+        (
+            "{icon} icon while charging (empty to use the level one)",
+            "Icono de {icon} mientras carga (vacío para usar el del nivel)",
+        ),
+        (
+            "Below this percentage it is flagged as a warning without a charger",
+            "Por debajo de este porcentaje se marca como aviso sin cargador",
+        ),
+        (
+            "Below this percentage it is flagged as critical without a charger",
+            "Por debajo de este porcentaje se marca como crítico sin cargador",
+        ),
+        (
+            "Battery to show (default the combined view)",
+            "Batería a mostrar (por defecto la vista combinada)",
+        ),
+        (
+            "Prints a single line and exits (tmux, i3blocks with interval)",
+            "Imprime una sola línea y termina (tmux, i3blocks con interval)",
+        ),
+        (
+            "Battery to check (default the combined view)",
+            "Batería a comprobar (por defecto la vista combinada)",
+        ),
+        (
+            "WARNING if the charge drops below this percentage while unplugged",
+            "WARNING si la carga baja de este porcentaje sin estar enchufado",
+        ),
+        (
+            "CRITICAL if the charge drops below this percentage while unplugged",
+            "CRITICAL si la carga baja de este porcentaje sin estar enchufado",
+        ),
+        (
+            "WARNING if the health drops below this percentage",
+            "WARNING si la salud baja de este porcentaje",
+        ),
+        (
+            "CRITICAL if the health drops below this percentage",
+            "CRITICAL si la salud baja de este porcentaje",
+        ),
+        (
+            "WARNING if it discharges faster than these points per hour (uses the history)",
+            "WARNING si se descarga más deprisa que estos puntos por hora (usa el historial)",
+        ),
+        (
+            "CRITICAL if it discharges faster than these points per hour (uses the history)",
+            "CRITICAL si se descarga más deprisa que estos puntos por hora (usa el historial)",
+        ),
+        (
+            "Minutes of history used to compute the discharge rate",
+            "Minutos de historial con los que se calcula la velocidad de descarga",
+        ),
+        (
+            "Battery to show or change (default all)",
+            "Batería a consultar o modificar (por defecto todas)",
+        ),
+        (
+            "Percentage below which charging starts",
+            "Porcentaje por debajo del cual empieza a cargar",
+        ),
+        ("Percentage at which charging stops", "Porcentaje en el que deja de cargar"),
+        (
+            "Charge mode of charge_behaviour (auto, inhibit-charge, force-discharge)",
+            "Modo de carga de charge_behaviour (auto, inhibit-charge, force-discharge)",
+        ),
+    ],
+    value_names: &[
+        ("PATH", "RUTA"),
+        ("SHELL", "INTÉRPRETE"),
+        ("DAYS", "DÍAS"),
+        ("LANGUAGE", "IDIOMA"),
+        ("SECONDS", "SEGUNDOS"),
+        ("FORMAT", "FORMATO"),
+        ("FIELDS", "CAMPOS"),
+        ("RULE", "REGLA"),
+        ("PORT", "PUERTO"),
+        ("ADDRESS", "DIRECCIÓN"),
+        ("HOURS", "HORAS"),
+        ("WHEN", "MOMENTO"),
+        ("NAME", "NOMBRE"),
+        ("FILE", "ARCHIVO"),
+        ("TEMPLATE", "PLANTILLA"),
+        ("ICONS", "ICONOS"),
+        ("ICON", "ICONO"),
+        ("PERCENT", "PORCENTAJE"),
+        ("POINTS", "PUNTOS"),
+        ("MINUTES", "MINUTOS"),
+        ("MODE", "MODO"),
+    ],
+
+    check_health: |health| format!("salud {}%", health),
+    check_discharge: |rate| format!("descarga {:.1} %/h", rate),
+    check_percent_below: |limit| format!("carga por debajo del {}%", limit),
+    check_health_below: |limit| format!("salud por debajo del {}%", limit),
+    check_discharge_above: |limit| format!("descarga por encima de {} %/h", limit),
+    check_no_health: "la batería no informa de su salud",
+    check_no_history: "sin historial suficiente para la velocidad de descarga",
+
+    source_default: "valor por defecto",
+    source_file: "archivo de configuración",
+    source_env: |var| format!("variable {}", var),
+    source_flag: |flag| format!("opción {}", flag),
+    config_file: |path, found| {
+        format!(
+            "Archivo: {}{}",
+            path.display(),
+            if found { "" } else { " (no existe)" }
+        )
+    },
+    config_error: |path, e| format!("Error en {}: {}", path.display(), e),
+    config_invalid: |key, message| format!("Configuración no válida ({}): {}", key, message),
+    interval_too_short: "el intervalo debe ser de al menos 1 segundo",
+
+    signal_handler_failed: |e| format!("No se pudo instalar el manejador de señales: {}", e),
+    watchdog_interval: |interval, watchdog| {
+        format!(
+            "Aviso: el intervalo ({} s) supera la mitad del watchdog de systemd ({} s)",
+            interval, watchdog
+        )
+    },
+    daemon_started: |interval, path, dedup| {
+        format!(
+            "Rusttery registrando cada {} s en {} (dedup: {})",
+            interval,
+            path.display(),
+            dedup
+        )
+    },
+    monitor_stopped: "El monitor de batería se ha detenido",
+    daemon_stopped: |saved| format!("Rusttery detenido ({} lecturas guardadas)", saved),
+    save_event_failed: |e| format!("Error al guardar el evento: {}", e),
+    save_reading_failed: |e| format!("Error al guardar la lectura: {}", e),
+    battery_unreadable: |e| format!("No se pudo leer la batería: {}", e),
+    daemon_status: |name, percent, saved| {
+        format!("{}: {}% | {} lecturas guardadas", name, percent, saved)
+    },
+    daemon_status_no_battery: |saved| format!("Sin batería | {} lecturas guardadas", saved),
+
+    internal_error: "Error interno en Rusttery",
+    invalid_utf8: "La cadena no es UTF-8 válido",
+    null_output: "El puntero de salida es nulo",
+    null_database: "Base de datos nula",
+    history_index_out_of_range: |index| format!("Índice {} fuera del historial", index),
+};
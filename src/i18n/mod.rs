@@ -0,0 +1,323 @@
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Deserializer};
+
+mod en;
+mod es;
+
+// Idioma de los mensajes de los frontales (CLI, GUI, TUI y API) y de los
+// errores de lectura. Los nombres de los campos JSON y los valores que
+// vienen del sistema, como el estado de la batería, no se traducen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Es,
+    En,
+}
+
+impl Language {
+    pub fn messages(self) -> &'static Messages {
+        match self {
+            Language::Es => &es::MESSAGES,
+            Language::En => &en::MESSAGES,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Es => write!(f, "es"),
+            Language::En => write!(f, "en"),
+        }
+    }
+}
+
+// Acepta el código del idioma o una locale completa como "en_GB.UTF-8".
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        match code.to_ascii_lowercase().as_str() {
+            "es" => Ok(Language::Es),
+            "en" => Ok(Language::En),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(|_| {
+            serde::de::Error::invalid_value(serde::de::Unexpected::Str(&value), &"es, en")
+        })
+    }
+}
+
+// 0 mientras no se haya elegido ni consultado el entorno.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+// Idioma de la locale del entorno, con la prioridad de POSIX: LC_ALL,
+// LC_MESSAGES y LANG. Devuelve también la variable de la que salió; con una
+// locale de otro idioma, o "C", no hay idioma.
+pub fn detect() -> Option<(Language, &'static str)> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|var| {
+            std::env::var(var)
+                .ok()
+                .filter(|value| !value.is_empty())
+                .map(|value| (var, value))
+        })
+        .and_then(|(var, value)| value.parse().ok().map(|language| (language, var)))
+}
+
+// Sin set_language se usa el idioma del entorno y, si no se reconoce,
+// inglés. El programa siempre lo fija desde la configuración, cuyo valor por
+// defecto es el español; esto solo afecta al usar Rusttery como biblioteca.
+pub fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::Es,
+        2 => Language::En,
+        _ => {
+            let language = detect().map_or(Language::En, |(language, _)| language);
+            set_language(language);
+            language
+        }
+    }
+}
+
+pub fn set_language(language: Language) {
+    let value = match language {
+        Language::Es => 1,
+        Language::En => 2,
+    };
+    CURRENT.store(value, Ordering::Relaxed);
+}
+
+// Textos del idioma actual.
+pub fn messages() -> &'static Messages {
+    language().messages()
+}
+
+type Display<'a> = &'a dyn fmt::Display;
+
+// Catálogo de mensajes. Los textos fijos son cadenas y los que llevan datos,
+// funciones, de modo que cada traducción comprueba sus argumentos al
+// compilar.
+pub struct Messages {
+    // Errores de lectura y de umbrales.
+    pub no_battery: &'static str,
+    pub unknown_battery: fn(&str) -> String,
+    pub access_failed: fn(&Path, Display) -> String,
+    pub read_failed: fn(&Path, Display) -> String,
+    pub write_failed: fn(&Path, Display) -> String,
+    pub invalid_value: fn(Display, &str) -> String,
+    pub call_failed: fn(&str, &str) -> String,
+    pub zero_capacity: fn(&str) -> String,
+    pub unsupported_platform: &'static str,
+    pub supported_platforms: &'static str,
+    pub thresholds_unsupported: fn(&str, &str) -> String,
+    pub hotplug_unavailable: fn(Display) -> String,
+    pub no_threshold: &'static str,
+    pub threshold_out_of_range: fn(u8) -> String,
+    pub threshold_order: fn(u8, u8) -> String,
+    pub behaviour_unavailable: fn(&str, &str) -> String,
+    pub read_error: &'static str,
+    pub monitor_thread_failed: &'static str,
+
+    // Nombres de los datos de la batería.
+    pub time: &'static str,
+    pub name: &'static str,
+    pub battery: &'static str,
+    pub total: &'static str,
+    pub charge: &'static str,
+    pub current_charge: &'static str,
+    pub percent_exact: &'static str,
+    pub health: &'static str,
+    pub status: &'static str,
+    pub cycles: &'static str,
+    pub voltage: &'static str,
+    pub current: &'static str,
+    pub power: &'static str,
+    pub technology: &'static str,
+    pub manufacturer: &'static str,
+    pub model: &'static str,
+    pub serial_number: &'static str,
+    pub capacity_unit: &'static str,
+    pub energy_now: &'static str,
+    pub energy_full: &'static str,
+    pub energy_full_design: &'static str,
+    pub charge_now: &'static str,
+    pub charge_full: &'static str,
+    pub charge_full_design: &'static str,
+    pub voltage_min_design: &'static str,
+    pub time_to_empty: &'static str,
+    pub time_to_full: &'static str,
+    pub time_to_empty_short: &'static str,
+    pub time_to_full_short: &'static str,
+    pub estimate: &'static str,
+    pub estimate_kernel: &'static str,
+    pub estimate_power: &'static str,
+    pub estimate_current: &'static str,
+    pub estimate_smoothed: &'static str,
+    pub temperature: &'static str,
+    pub capacity_level: &'static str,
+    pub alarm: &'static str,
+    pub present: &'static str,
+    pub health_status: &'static str,
+    pub read_method: &'static str,
+    pub read_method_files: &'static str,
+    pub records: &'static str,
+    pub avg_percent: &'static str,
+    pub min_percent: &'static str,
+    pub max_percent: &'static str,
+    pub avg_power: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub unknown_status: &'static str,
+
+    // Cargadores y periféricos.
+    pub power_supply: &'static str,
+    pub on_battery: &'static str,
+    pub mains: &'static str,
+    pub connected: &'static str,
+    pub disconnected: &'static str,
+    pub usb_type: &'static str,
+    pub current_max: &'static str,
+    pub power_max: &'static str,
+    pub peripherals: &'static str,
+    pub device: &'static str,
+    pub level: &'static str,
+
+    // Eventos de conexión y avisos.
+    pub event: &'static str,
+    pub charger_connected: fn(&str) -> String,
+    pub charger_disconnected: fn(&str) -> String,
+    pub battery_added: fn(&str) -> String,
+    pub battery_removed: fn(&str) -> String,
+    pub status_changed: fn(&str, &str) -> String,
+    pub warning: &'static str,
+    pub threshold_crossed: fn(&str, i32, bool) -> String,
+
+    // GUI y TUI.
+    pub details: &'static str,
+    pub current_reading: &'static str,
+    pub history_title: fn(&str) -> String,
+    pub days: fn(i64) -> String,
+    pub database_unavailable: &'static str,
+    pub no_records_hours: fn(i64) -> String,
+    pub daemon_hint: &'static str,
+    pub max_short: &'static str,
+    pub no_data: &'static str,
+    pub recent_changes: &'static str,
+    pub no_changes: &'static str,
+    pub tui_help: &'static str,
+    pub gui_failed: &'static str,
+    pub tui_failed: &'static str,
+
+    // API REST.
+    pub server_running: fn(&str, u16) -> String,
+    pub server_failed: fn(&str, u16) -> String,
+    pub database_failed: &'static str,
+    pub endpoints: &'static str,
+    pub endpoint_descriptions: [&'static str; 12],
+    pub battery_param_hint: &'static str,
+    pub route_not_found: &'static str,
+    pub method_not_allowed: &'static str,
+    pub body_unreadable: &'static str,
+    pub invalid_json: &'static str,
+    pub writes_disabled: &'static str,
+    pub invalid_token: &'static str,
+    pub history_failed: &'static str,
+    pub statistics_failed: &'static str,
+    pub events_failed: &'static str,
+
+    // Línea de comandos.
+    pub error: &'static str,
+    pub serialize_failed: &'static str,
+    pub unknown_field: fn(&str, &str) -> String,
+    pub fields_required: &'static str,
+    pub charge_start: &'static str,
+    pub charge_end: &'static str,
+    pub charge_mode: &'static str,
+    pub threshold_permission: &'static str,
+    pub until_before_since: fn(&str, &str) -> String,
+    pub no_records: fn(Option<&str>, &str, Option<&str>) -> String,
+    pub database_open_failed: fn(&Path, Display) -> String,
+    pub create_failed: fn(&Path, Display) -> String,
+    pub exported: fn(usize, &Path) -> String,
+    pub export_failed: &'static str,
+    pub template_unclosed: fn(&str) -> String,
+    pub template_unopened: fn(&str) -> String,
+    pub template_unknown_field: fn(&str, &str) -> String,
+    pub template_invalid_format: fn(&str, &str) -> String,
+    pub invalid_time: &'static str,
+    pub invalid_language: &'static str,
+
+    // Traducción de la ayuda de la línea de comandos, que se escribe en
+    // inglés en los comentarios `///` de args.rs: texto original y traducido.
+    pub help: &'static [(&'static str, &'static str)],
+    // Nombres de los valores de las opciones (<SEGUNDOS>), por el nombre en
+    // inglés con el que se declaran.
+    pub value_names: &'static [(&'static str, &'static str)],
+
+    // Comprobación para Nagios/Icinga.
+    pub check_health: fn(i32) -> String,
+    pub check_discharge: fn(f64) -> String,
+    pub check_percent_below: fn(u8) -> String,
+    pub check_health_below: fn(u8) -> String,
+    pub check_discharge_above: fn(f64) -> String,
+    pub check_no_health: &'static str,
+    pub check_no_history: &'static str,
+
+    // Configuración.
+    pub source_default: &'static str,
+    pub source_file: &'static str,
+    pub source_env: fn(&str) -> String,
+    pub source_flag: fn(&str) -> String,
+    pub config_file: fn(&Path, bool) -> String,
+    pub config_error: fn(&Path, Display) -> String,
+    pub config_invalid: fn(&str, &str) -> String,
+    pub interval_too_short: &'static str,
+
+    // Recolector (`rusttery daemon`).
+    pub signal_handler_failed: fn(Display) -> String,
+    pub watchdog_interval: fn(u64, u64) -> String,
+    pub daemon_started: fn(u64, &Path, Display) -> String,
+    pub monitor_stopped: &'static str,
+    pub daemon_stopped: fn(u64) -> String,
+    pub save_event_failed: fn(Display) -> String,
+    pub save_reading_failed: fn(Display) -> String,
+    pub battery_unreadable: fn(Display) -> String,
+    pub daemon_status: fn(&str, i32, u64) -> String,
+    pub daemon_status_no_battery: fn(u64) -> String,
+
+    // Biblioteca C.
+    pub internal_error: &'static str,
+    pub invalid_utf8: &'static str,
+    pub null_output: &'static str,
+    pub null_database: &'static str,
+    pub history_index_out_of_range: fn(usize) -> String,
+}
+
+impl Messages {
+    pub fn help(&self, key: &str) -> Option<&'static str> {
+        lookup(self.help, key)
+    }
+
+    pub fn value_name(&self, name: &str) -> Option<&'static str> {
+        lookup(self.value_names, name)
+    }
+}
+
+fn lookup(table: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(entry, _)| *entry == key)
+        .map(|(_, text)| *text)
+}
@@ -0,0 +1,26 @@
+//! Lectura de baterías en Linux y Windows, historial en SQLite y los
+//! frontales (GUI, TUI, API REST) de Rusttery. Las características `gui`,
+//! `tui`, `api` y `database` permiten integrar solo la lectura de baterías.
+//! Los mensajes están en español e inglés (módulo `i18n`).
+
+pub mod core;
+pub mod i18n;
+#[cfg(feature = "database")]
+pub mod database;
+#[cfg(feature = "api")]
+pub mod api;
+#[cfg(feature = "gui")]
+pub mod gui;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+mod python;
+
+pub use crate::core::{
+    combine_batteries, default_source, get_all_batteries, get_battery_info, BatteryChange,
+    BatteryError, BatteryInfo, BatteryMonitor, BatterySource, ChangeFilter, Snapshot,
+};
+#[cfg(feature = "database")]
+pub use crate::database::{Database, EventRecord, HistoryRecord, Statistics};
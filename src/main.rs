@@ -1,31 +1,147 @@
-mod core;
-mod api;
 mod cli;
-mod gui;
-mod database;
+mod config;
+mod daemon;
 
-use std::env;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rusttery::api::{self, ServerOptions};
+use rusttery::i18n::{self, messages};
+use rusttery::{default_source, gui, tui, BatteryMonitor, BatterySource};
+
+use cli::args::{Cli, Command, ConfigAction, MonitorArgs, ServeArgs, WatchArgs};
+use config::{Config, Overrides};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    
-    if args.contains(&"--api".to_string()) {
-        api::start_server();
-        return;
-    }
+    // La ayuda y los errores de los argumentos ya salen en el idioma elegido.
+    i18n::set_language(config::language_before_parsing(
+        &std::env::args().collect::<Vec<_>>(),
+    ));
 
-    if args.contains(&"--cli".to_string()) {
-        cli::run();
-        return;
-    }
+    let args = Cli::try_parse_localized().unwrap_or_else(|e| {
+        // Para Nagios/Icinga un 2 sería CRITICAL; los errores de uso de
+        // `check` salen con 3 (UNKNOWN).
+        let code = if e.use_stderr() && std::env::args().any(|arg| arg == "check") {
+            3
+        } else {
+            e.exit_code()
+        };
+        let _ = e.print();
+        std::process::exit(code);
+    });
+
+    let command = match args.command {
+        Some(command) => command,
+        None if args.api => Command::Serve(ServeArgs {
+            port: None,
+            bind: None,
+            monitor: MonitorArgs::default(),
+        }),
+        None if args.cli => Command::Watch(WatchArgs::default()),
+        None if args.json => Command::Json,
+        None => Command::Gui(MonitorArgs::default()),
+    };
 
-    if args.contains(&"--json".to_string()) {
-        cli::run_json();
-        return;
+    let mut overrides = Overrides {
+        language: args.language,
+        db_path: args.db_path,
+        retention_days: args.retention_days,
+        ..Overrides::default()
+    };
+    match &command {
+        Command::Gui(monitor) | Command::Tui(monitor) => overrides.interval = monitor.interval,
+        Command::Watch(watch) => overrides.interval = watch.monitor.interval,
+        Command::Bar(bar) => overrides.interval = bar.monitor.interval,
+        Command::Daemon(daemon) => {
+            overrides.interval = daemon.monitor.interval;
+            overrides.dedup = daemon.dedup;
+        }
+        Command::Serve(serve) => {
+            overrides.interval = serve.monitor.interval;
+            overrides.port = serve.port;
+            overrides.bind = serve.bind.clone();
+        }
+        _ => {}
     }
 
-    if let Err(e) = gui::run() {
-        eprintln!("Error al iniciar la GUI: {}", e);
-        std::process::exit(1);
+    let config = match Config::load(args.config.as_deref(), overrides) {
+        Ok(config) => config,
+        Err(e) => exit_with_error(&command, e),
+    };
+
+    let source: Arc<dyn BatterySource> = match default_source() {
+        Ok(source) => Arc::from(source),
+        Err(e) => exit_with_error(&command, e),
+    };
+
+    let monitor =
+        || BatteryMonitor::start(source.clone(), Duration::from_secs(config.interval.value));
+
+    let exit_code = match command {
+        Command::Gui(_) => match gui::run(monitor()) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{}: {}", messages().gui_failed, e);
+                1
+            }
+        },
+        Command::Tui(_) => match tui::run(monitor(), config.open_database_for_reading().ok()) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("{}: {}", messages().tui_failed, e);
+                1
+            }
+        },
+        Command::Watch(args) => cli::run(&monitor(), &config, &args),
+        Command::Daemon(_) => daemon::run(&monitor(), &config),
+        Command::Serve(_) => {
+            let options = ServerOptions {
+                bind: config.bind.value.clone(),
+                port: config.port.value,
+                db_path: Some(config.db_path.value.clone()),
+                retention_days: config.retention_days.value,
+            };
+            api::start_server(&monitor(), &options);
+            0
+        }
+        Command::Status(args) => cli::run_status(source.as_ref(), &args),
+        Command::Json => {
+            cli::run_json(source.as_ref());
+            0
+        }
+        Command::History(args) => cli::run_history(&config, &args),
+        Command::Stats(args) => cli::run_stats(&config, &args),
+        Command::Export(args) => cli::run_export(&config, &args),
+        Command::Bar(args) => cli::run_bar(&monitor(), &args),
+        Command::Check(args) => cli::run_check(source.as_ref(), &config, &args),
+        Command::Threshold(args) => cli::run_threshold(source.as_ref(), &args),
+        Command::Config {
+            action: ConfigAction::Show,
+        } => {
+            println!("{}", config.show());
+            0
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::localized_command(),
+                "rusttery",
+                &mut io::stdout(),
+            );
+            0
+        }
+    };
+
+    std::process::exit(exit_code);
+}
+
+// Errores previos a ejecutar la orden; `check` los informa como UNKNOWN.
+fn exit_with_error(command: &Command, error: impl std::fmt::Display) -> ! {
+    if matches!(command, Command::Check(_)) {
+        println!("BATTERY UNKNOWN - {}", error);
+        std::process::exit(3);
     }
+    eprintln!("{}", error);
+    std::process::exit(2);
 }
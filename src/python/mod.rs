@@ -0,0 +1,165 @@
+// Módulo de Python `rusttery`. Las lecturas, el historial y las
+// estadísticas se devuelven como diccionarios con las mismas claves que la
+// API REST, de modo que se pueden pasar directamente a pandas.DataFrame.
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::Value;
+
+use crate::core;
+use crate::database;
+
+create_exception!(
+    rusttery,
+    BatteryError,
+    PyException,
+    "No se pudo leer la batería. `code` contiene el código de error de la API."
+);
+create_exception!(
+    rusttery,
+    DatabaseError,
+    PyException,
+    "Error al abrir o consultar la base de datos del historial."
+);
+
+fn battery_error(py: Python<'_>, error: core::BatteryError) -> PyErr {
+    let err = BatteryError::new_err(error.to_string());
+    if let Err(e) = err.value(py).setattr("code", error.code()) {
+        return e;
+    }
+    err
+}
+
+fn database_error(error: rusqlite::Error) -> PyErr {
+    DatabaseError::new_err(error.to_string())
+}
+
+// Se pasa por el texto JSON para que los f32 lleguen a Python con el mismo
+// redondeo que en la API (12.3 y no 12.300000190734863).
+fn to_python<'py>(py: Python<'py>, value: &impl serde::Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_string(value)
+        .and_then(|json| serde_json::from_str::<Value>(&json))
+        .map_err(|e| PyException::new_err(e.to_string()))?;
+    value_to_python(py, &value)
+}
+
+fn value_to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_pyobject(py)?.into_any(),
+            None => n.as_f64().into_pyobject(py)?.into_any(),
+        },
+        Value::String(s) => s.into_pyobject(py)?.into_any(),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| value_to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, value_to_python(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+/// Lectura de la batería indicada o, sin nombre, de la vista combinada.
+#[pyfunction]
+#[pyo3(signature = (name = None))]
+fn get_battery_info<'py>(py: Python<'py>, name: Option<&str>) -> PyResult<Bound<'py, PyAny>> {
+    let result = py.allow_threads(|| match name {
+        Some(name) => core::get_all_batteries()?
+            .into_iter()
+            .find(|b| b.name == name)
+            .ok_or_else(|| core::BatteryError::UnknownBattery(name.to_string())),
+        None => core::get_battery_info(),
+    });
+
+    let info = result.map_err(|e| battery_error(py, e))?;
+    to_python(py, &info)
+}
+
+/// Lista con la lectura de cada batería.
+#[pyfunction]
+fn get_all_batteries<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+    let batteries = py
+        .allow_threads(core::get_all_batteries)
+        .map_err(|e| battery_error(py, e))?;
+    to_python(py, &batteries)
+}
+
+/// Historial de SQLite. Sin ruta se abre la base de datos que usan la CLI
+/// y la API. Con `retention_days` mayor que 0 se borran los registros más
+/// antiguos de ese número de días; por defecto se conserva todo.
+#[pyclass(name = "Database", module = "rusttery")]
+struct PyDatabase(Mutex<database::Database>);
+
+impl PyDatabase {
+    fn query<T: serde::Serialize + Send>(
+        &self,
+        py: Python<'_>,
+        query: impl FnOnce(&database::Database) -> rusqlite::Result<T> + Send,
+    ) -> PyResult<Py<PyAny>> {
+        let result = py.allow_threads(|| query(&self.0.lock().unwrap()));
+        Ok(to_python(py, &result.map_err(database_error)?)?.unbind())
+    }
+}
+
+#[pymethods]
+impl PyDatabase {
+    #[new]
+    #[pyo3(signature = (path = None, retention_days = 0))]
+    fn new(path: Option<PathBuf>, retention_days: u32) -> PyResult<Self> {
+        let path = path.unwrap_or_else(database::Database::default_path);
+        let db = database::Database::open_with_retention(path, retention_days);
+        Ok(Self(Mutex::new(db.map_err(database_error)?)))
+    }
+
+    /// Registros de las últimas `hours` horas, del más antiguo al más
+    /// reciente.
+    #[pyo3(signature = (hours = 24, battery = None))]
+    fn get_history(
+        &self,
+        py: Python<'_>,
+        hours: i64,
+        battery: Option<&str>,
+    ) -> PyResult<Py<PyAny>> {
+        self.query(py, |db| db.get_history(hours, battery))
+    }
+
+    #[pyo3(signature = (hours = 24, battery = None))]
+    fn get_statistics(
+        &self,
+        py: Python<'_>,
+        hours: i64,
+        battery: Option<&str>,
+    ) -> PyResult<Py<PyAny>> {
+        self.query(py, |db| db.get_statistics(hours, battery))
+    }
+
+    /// Conexiones de cargadores y baterías de las últimas `hours` horas.
+    #[pyo3(signature = (hours = 24))]
+    fn get_events(&self, py: Python<'_>, hours: i64) -> PyResult<Py<PyAny>> {
+        self.query(py, |db| db.get_events(hours))
+    }
+}
+
+#[pymodule]
+fn rusttery(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(get_battery_info, m)?)?;
+    m.add_function(wrap_pyfunction!(get_all_batteries, m)?)?;
+    m.add_class::<PyDatabase>()?;
+    m.add("BatteryError", m.py().get_type::<BatteryError>())?;
+    m.add("DatabaseError", m.py().get_type::<DatabaseError>())?;
+    Ok(())
+}
@@ -0,0 +1,524 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, TimeZone};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, List, ListItem, Paragraph, Sparkline, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::core::{BatteryInfo, BatteryMonitor, PowerEvent, Snapshot};
+use crate::database::{Database, Statistics};
+use crate::i18n::messages;
+
+// Intervalos de las estadísticas en horas, que se eligen con las teclas 1-4.
+const RANGES: [i64; 4] = [1, 6, 24, 168];
+const DEFAULT_RANGE: usize = 2;
+
+// Lecturas que se guardan en memoria para las gráficas y cambios de estado
+// que se muestran.
+const MAX_SAMPLES: usize = 1000;
+const MAX_TRANSITIONS: usize = 100;
+
+// Cada cuánto se vuelven a consultar las estadísticas aunque no cambie la
+// selección, y cuánto se espera a una tecla antes de mirar si hay lecturas
+// nuevas.
+const STATS_REFRESH: Duration = Duration::from_secs(30);
+const POLL: Duration = Duration::from_millis(250);
+
+struct Sample {
+    percent: u64,
+    // Centésimas de vatio, porque Sparkline solo admite enteros.
+    power: Option<u64>,
+}
+
+struct Transition {
+    timestamp: i64,
+    text: String,
+}
+
+// Panel de terminal a pantalla completa: lectura actual, gráficas de
+// potencia y carga, cambios de estado recientes y estadísticas del
+// historial. La base de datos solo se lee; las lecturas las guarda
+// `rusttery daemon` o `rusttery watch`.
+pub struct Dashboard {
+    monitor: BatteryMonitor,
+    db: Option<Database>,
+    snapshot: Arc<Snapshot>,
+    // None es la vista combinada de todas las baterías.
+    selected: Option<String>,
+    range: usize,
+    samples: HashMap<Option<String>, VecDeque<Sample>>,
+    transitions: VecDeque<Transition>,
+    stats: Option<Result<Statistics, String>>,
+    stats_updated: Option<Instant>,
+}
+
+impl Dashboard {
+    pub fn new(monitor: BatteryMonitor, db: Option<Database>) -> Self {
+        let mut dashboard = Self {
+            snapshot: Arc::new(Snapshot::default()),
+            monitor,
+            db,
+            selected: None,
+            range: DEFAULT_RANGE,
+            samples: HashMap::new(),
+            transitions: VecDeque::new(),
+            stats: None,
+            stats_updated: None,
+        };
+
+        // Los eventos ya guardados dan contexto al abrir el panel.
+        if let Some(db) = &dashboard.db {
+            for record in db.get_events(24).unwrap_or_default() {
+                dashboard.push_transition(
+                    record.timestamp,
+                    event_text(&record.event, &record.name, record.status.as_deref()),
+                );
+            }
+        }
+
+        let snapshot = dashboard.monitor.latest();
+        dashboard.update(snapshot);
+        dashboard
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        snapshots: &Receiver<Arc<Snapshot>>,
+    ) -> io::Result<()> {
+        loop {
+            self.refresh_stats();
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(POLL)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                        return Ok(());
+                    }
+                }
+            }
+
+            while let Ok(snapshot) = snapshots.try_recv() {
+                self.update(snapshot);
+            }
+        }
+    }
+
+    fn update(&mut self, snapshot: Arc<Snapshot>) {
+        let now = Local::now().timestamp();
+
+        // Las transiciones de estado se detectan comparando lecturas, así que
+        // también aparecen sin eventos de udev; los de estado se ignoran para
+        // no repetirlas.
+        for event in &snapshot.events {
+            if !matches!(event, PowerEvent::StatusChanged { .. }) {
+                self.push_transition(
+                    now,
+                    event_text(event.as_str(), event.name(), event.status()),
+                );
+            }
+        }
+        for info in &snapshot.batteries {
+            let previous = self.snapshot.batteries.iter().find(|b| b.name == info.name);
+            if let Some(previous) = previous.filter(|p| p.status != info.status) {
+                let unknown = messages().unknown_status;
+                self.push_transition(
+                    now,
+                    format!(
+                        "{}: {} → {}",
+                        info.name,
+                        previous.status.as_deref().unwrap_or(unknown),
+                        info.status.as_deref().unwrap_or(unknown)
+                    ),
+                );
+            }
+        }
+
+        for info in &snapshot.batteries {
+            self.push_sample(Some(info.name.clone()), info);
+        }
+        if let Some(combined) = snapshot
+            .combined
+            .as_ref()
+            .filter(|_| snapshot.batteries.len() > 1)
+        {
+            self.push_sample(None, combined);
+        }
+
+        self.snapshot = snapshot;
+
+        // Si la batería elegida desaparece se vuelve a la primera opción.
+        let choices = self.choices();
+        if !choices.contains(&self.selected) {
+            self.selected = choices.into_iter().next().flatten();
+            self.stats_updated = None;
+        }
+    }
+
+    fn push_sample(&mut self, key: Option<String>, info: &BatteryInfo) {
+        let samples = self.samples.entry(key).or_default();
+        samples.push_back(Sample {
+            percent: info.percent.clamp(0, 100) as u64,
+            power: info.power_now.map(|w| (w.abs() * 100.0).round() as u64),
+        });
+        if samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    fn push_transition(&mut self, timestamp: i64, text: String) {
+        self.transitions.push_front(Transition { timestamp, text });
+        self.transitions.truncate(MAX_TRANSITIONS);
+    }
+
+    // Con varias baterías, la vista combinada y cada una por separado.
+    fn choices(&self) -> Vec<Option<String>> {
+        let names = self.snapshot.batteries.iter().map(|b| Some(b.name.clone()));
+        if self.snapshot.batteries.len() > 1 {
+            std::iter::once(None).chain(names).collect()
+        } else {
+            names.collect()
+        }
+    }
+
+    fn refresh_stats(&mut self) {
+        let Some(db) = &self.db else {
+            return;
+        };
+        if self
+            .stats_updated
+            .is_some_and(|at| at.elapsed() < STATS_REFRESH)
+        {
+            return;
+        }
+
+        let hours = RANGES[self.range];
+        self.stats = Some(
+            db.get_statistics(hours, self.selected.as_deref())
+                .map_err(|e| e.to_string()),
+        );
+        self.stats_updated = Some(Instant::now());
+    }
+
+    // Devuelve false para salir.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.select(1),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => self.select(-1),
+            KeyCode::Char(c @ '1'..='4') => {
+                self.range = c as usize - '1' as usize;
+                self.stats_updated = None;
+            }
+            KeyCode::Char('r') => {
+                self.range = (self.range + 1) % RANGES.len();
+                self.stats_updated = None;
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn select(&mut self, step: isize) {
+        let choices = self.choices();
+        if choices.is_empty() {
+            return;
+        }
+
+        let current = choices
+            .iter()
+            .position(|c| c == &self.selected)
+            .unwrap_or(0);
+        let next = (current as isize + step).rem_euclid(choices.len() as isize) as usize;
+        self.selected = choices[next].clone();
+        self.stats_updated = None;
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let t = messages();
+        let [tabs, gauge, details, power, percent, transitions, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Length(10),
+            Constraint::Min(5),
+            Constraint::Min(5),
+            Constraint::Min(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.draw_tabs(frame, tabs);
+
+        match self.snapshot.battery(self.selected.as_deref()) {
+            Ok(info) => {
+                self.draw_gauge(frame, gauge, &info);
+                let [current, stats] =
+                    Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                        .areas(details);
+                frame.render_widget(
+                    Paragraph::new(detail_lines(&info))
+                        .block(Block::bordered().title(format!(" {} ", t.current_reading))),
+                    current,
+                );
+                self.draw_stats(frame, stats);
+            }
+            Err(e) => {
+                frame.render_widget(
+                    Paragraph::new(vec![
+                        Line::styled(t.read_error, Style::default().fg(Color::Red)),
+                        Line::from(e.to_string()),
+                    ])
+                    .block(Block::bordered()),
+                    gauge.union(details),
+                );
+            }
+        }
+
+        self.draw_sparklines(frame, power, percent);
+        self.draw_transitions(frame, transitions);
+
+        frame.render_widget(
+            Paragraph::new(t.tui_help).style(Style::default().fg(Color::DarkGray)),
+            help,
+        );
+    }
+
+    fn draw_tabs(&self, frame: &mut Frame, area: Rect) {
+        let choices = self.choices();
+        let titles: Vec<String> = choices
+            .iter()
+            .map(|c| c.clone().unwrap_or_else(|| messages().total.to_string()))
+            .collect();
+        let selected = choices.iter().position(|c| c == &self.selected);
+
+        let [title, tabs] =
+            Layout::horizontal([Constraint::Length(12), Constraint::Min(0)]).areas(area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "🔋 Rusttery",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            title,
+        );
+        frame.render_widget(
+            Tabs::new(titles).select(selected).highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            tabs,
+        );
+    }
+
+    fn draw_gauge(&self, frame: &mut Frame, area: Rect, info: &BatteryInfo) {
+        let percent = info.percent.clamp(0, 100);
+        let color = match percent {
+            0..=10 => Color::Red,
+            11..=25 => Color::Yellow,
+            _ => Color::Green,
+        };
+        let label = match &info.status {
+            Some(status) => format!("{}% · {}", percent, status),
+            None => format!("{}%", percent),
+        };
+
+        frame.render_widget(
+            Gauge::default()
+                .block(Block::bordered().title(format!(" {} ", info.name)))
+                .gauge_style(Style::default().fg(color))
+                .percent(percent as u16)
+                .label(label),
+            area,
+        );
+    }
+
+    fn draw_stats(&self, frame: &mut Frame, area: Rect) {
+        let t = messages();
+        let hours = RANGES[self.range];
+        let block =
+            Block::bordered().title(format!(" {} ", (t.history_title)(&range_label(hours))));
+
+        let lines = match &self.stats {
+            None if self.db.is_none() => vec![Line::from(t.database_unavailable)],
+            None => Vec::new(),
+            Some(Err(e)) => vec![Line::styled(e.clone(), Style::default().fg(Color::Red))],
+            Some(Ok(stats)) if stats.total_records == 0 => vec![
+                Line::from((t.no_records_hours)(hours)),
+                Line::styled(t.daemon_hint, Style::default().fg(Color::DarkGray)),
+            ],
+            Some(Ok(stats)) => vec![
+                Line::from(format!("{}: {}", t.records, stats.total_records)),
+                Line::from(format!("{}: {:.1}%", t.avg_percent, stats.avg_percent)),
+                Line::from(format!("{}: {}%", t.min_percent, stats.min_percent)),
+                Line::from(format!("{}: {}%", t.max_percent, stats.max_percent)),
+                Line::from(format!("{}: {:.2} W", t.avg_power, stats.avg_power)),
+            ],
+        };
+
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    // Las gráficas avanzan hacia la izquierda con cada lectura y muestran
+    // tantas como columnas caben.
+    fn draw_sparklines(&self, frame: &mut Frame, power_area: Rect, percent_area: Rect) {
+        let t = messages();
+        let samples = self.samples.get(&self.selected);
+        let visible = |area: Rect| {
+            let width = area.width.saturating_sub(2) as usize;
+            samples
+                .into_iter()
+                .flat_map(move |s| s.iter().skip(s.len().saturating_sub(width)))
+        };
+
+        let power: Vec<Option<u64>> = visible(power_area).map(|s| s.power).collect();
+        let current = power.last().copied().flatten();
+        let peak = power.iter().flatten().max().copied();
+        let title = match (current, peak) {
+            (Some(current), Some(peak)) => format!(
+                " {}: {:.2} W ({} {:.2} W) ",
+                t.power,
+                current as f64 / 100.0,
+                t.max_short,
+                peak as f64 / 100.0
+            ),
+            _ => format!(" {}: {} ", t.power, t.no_data),
+        };
+        frame.render_widget(
+            Sparkline::default()
+                .block(Block::bordered().title(title))
+                .data(&power)
+                .style(Style::default().fg(Color::Magenta)),
+            power_area,
+        );
+
+        let percent: Vec<u64> = visible(percent_area).map(|s| s.percent).collect();
+        let title = match percent.last() {
+            Some(current) => format!(" {}: {}% ", t.charge, current),
+            None => format!(" {}: {} ", t.charge, t.no_data),
+        };
+        frame.render_widget(
+            Sparkline::default()
+                .block(Block::bordered().title(title))
+                .data(&percent)
+                .max(100)
+                .style(Style::default().fg(Color::Green)),
+            percent_area,
+        );
+    }
+
+    fn draw_transitions(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .transitions
+            .iter()
+            .map(|t| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}  ", format_time(t.timestamp)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(t.text.clone()),
+                ]))
+            })
+            .collect();
+
+        let t = messages();
+        let block = Block::bordered().title(format!(" {} ", t.recent_changes));
+        if items.is_empty() {
+            frame.render_widget(Paragraph::new(t.no_changes).block(block), area);
+        } else {
+            frame.render_widget(List::new(items).block(block), area);
+        }
+    }
+}
+
+fn detail_lines(info: &BatteryInfo) -> Vec<Line<'static>> {
+    let t = messages();
+    let mut lines = Vec::new();
+    let mut add =
+        |label: &str, value: String| lines.push(Line::from(format!("{}: {}", label, value)));
+
+    if let Some(health) = info.health {
+        add(t.health, format!("{}%", health));
+    }
+    if let Some(power) = info.power_now {
+        add(t.power, format!("{:.2} W", power));
+    }
+    if let Some(voltage) = info.voltage_now {
+        add(t.voltage, format!("{:.2} V", voltage));
+    }
+    if let Some(current) = info.current_now {
+        add(t.current, format!("{:.2} A", current));
+    }
+    if let Some(time) = info.time_to_empty {
+        add(t.time_to_empty, format_minutes(time));
+    }
+    if let Some(time) = info.time_to_full {
+        add(t.time_to_full, format_minutes(time));
+    }
+    if let Some(temperature) = info.temperature {
+        add(t.temperature, format!("{:.1} °C", temperature));
+    }
+    if let Some(cycles) = info.cycle_count {
+        add(t.cycles, cycles.to_string());
+    }
+
+    lines
+}
+
+fn event_text(event: &str, name: &str, status: Option<&str>) -> String {
+    let t = messages();
+    match event {
+        "charger_connected" => (t.charger_connected)(name),
+        "charger_disconnected" => (t.charger_disconnected)(name),
+        "battery_added" => (t.battery_added)(name),
+        "battery_removed" => (t.battery_removed)(name),
+        "status_changed" => (t.status_changed)(name, status.unwrap_or(t.unknown_status)),
+        other => format!("{} ({})", other, name),
+    }
+}
+
+// "24 h" o, para semanas, "7 días".
+fn range_label(hours: i64) -> String {
+    if hours > 24 && hours % 24 == 0 {
+        (messages().days)(hours / 24)
+    } else {
+        format!("{} h", hours)
+    }
+}
+
+fn format_minutes(minutes: i32) -> String {
+    if minutes >= 60 {
+        format!("{} h {:02} min", minutes / 60, minutes % 60)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
+// Solo la hora si es de hoy.
+fn format_time(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0).single() {
+        Some(time) if time.date_naive() == Local::now().date_naive() => {
+            time.format("%H:%M:%S").to_string()
+        }
+        Some(time) => time.format("%Y-%m-%d %H:%M").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+pub fn run(monitor: BatteryMonitor, db: Option<Database>) -> io::Result<()> {
+    let snapshots = monitor.subscribe();
+    let mut dashboard = Dashboard::new(monitor, db);
+
+    let mut terminal = ratatui::try_init()?;
+    let result = dashboard.run(&mut terminal, &snapshots);
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,102 @@
+/* Prueba de la interfaz C contra baterías simuladas:
+ *   make -C tests/c
+ */
+#include <assert.h>
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "rusttery.h"
+
+static void print_error(const char *context) {
+    char *message = rusttery_last_error();
+    fprintf(stderr, "%s: %s\n", context, message ? message : "(sin mensaje)");
+    rusttery_string_free(message);
+}
+
+static void test_combined(void) {
+    RustteryBattery *battery = NULL;
+    RustteryStatus status = rusttery_battery_read(NULL, &battery);
+    if (status != RUSTTERY_STATUS_OK) {
+        print_error("rusttery_battery_read");
+    }
+    assert(status == RUSTTERY_STATUS_OK);
+
+    char *name = rusttery_battery_name(battery);
+    assert(strcmp(name, "BAT0+BAT1") == 0);
+    assert(rusttery_battery_percent(battery) == 60);
+    assert(rusttery_battery_health(battery) == -1);
+    assert(isnan(rusttery_battery_power_now(battery)));
+
+    char *json = rusttery_battery_json(battery);
+    assert(json != NULL && strstr(json, "\"percent\":60") != NULL);
+
+    printf("%s: %d%%\n", name, rusttery_battery_percent(battery));
+    rusttery_string_free(json);
+    rusttery_string_free(name);
+    rusttery_battery_free(battery);
+}
+
+static void test_list(void) {
+    RustteryBatteryList *list = NULL;
+    assert(rusttery_battery_list(&list) == RUSTTERY_STATUS_OK);
+    assert(rusttery_battery_list_len(list) == 2);
+
+    const RustteryBattery *second = rusttery_battery_list_get(list, 1);
+    char *status = rusttery_battery_status(second);
+    assert(strcmp(status, "Charging") == 0);
+    assert(rusttery_battery_list_get(list, 2) == NULL);
+
+    rusttery_string_free(status);
+    rusttery_battery_list_free(list);
+}
+
+static void test_errors(void) {
+    RustteryBattery *battery = NULL;
+    assert(rusttery_battery_read("BAT9", &battery) == RUSTTERY_STATUS_UNKNOWN_BATTERY);
+    assert(battery == NULL);
+
+    char *message = rusttery_last_error();
+    assert(message != NULL && strstr(message, "BAT9") != NULL);
+    rusttery_string_free(message);
+
+    assert(rusttery_battery_read(NULL, NULL) == RUSTTERY_STATUS_INVALID_ARGUMENT);
+}
+
+static void test_database(const char *path) {
+    RustteryDatabase *db = NULL;
+    RustteryStatus status = rusttery_database_open(path, 0, &db);
+    if (status != RUSTTERY_STATUS_OK) {
+        print_error("rusttery_database_open");
+    }
+    assert(status == RUSTTERY_STATUS_OK);
+
+    RustteryHistory *history = NULL;
+    assert(rusttery_database_history(db, 24, NULL, &history) == RUSTTERY_STATUS_OK);
+    size_t count = rusttery_history_len(history);
+    for (size_t i = 0; i < count; i++) {
+        RustteryHistoryRecord record;
+        assert(rusttery_history_get(history, i, &record) == RUSTTERY_STATUS_OK);
+        assert(record.percent >= 0 && record.percent <= 100);
+    }
+
+    RustteryHistoryRecord record;
+    assert(rusttery_history_get(history, count, &record) == RUSTTERY_STATUS_INVALID_ARGUMENT);
+
+    RustteryStatistics stats;
+    assert(rusttery_database_statistics(db, 24, "BAT0", &stats) == RUSTTERY_STATUS_OK);
+    assert(stats.total_records >= 0);
+
+    printf("Historial: %zu registros\n", count);
+    rusttery_history_free(history);
+    rusttery_database_free(db);
+}
+
+int main(int argc, char **argv) {
+    test_combined();
+    test_list();
+    test_errors();
+    test_database(argc > 1 ? argv[1] : "test_rusttery.db");
+    puts("OK");
+    return 0;
+}